@@ -3,30 +3,291 @@
 //! on our own system.
 //!
 //! Note: Identifiers in this module can be Amber GeoStd/PDBe (Should be same), or PubChem.
+//!
+//! These files don't change for a given molecule. Use [`set_cache_dir`] to store them locally, so
+//! they're only downloaded once.
+//!
+//! Use [`set_hosts`] to add mirrors, which are tried in order if a host is unavailable, and
+//! [`set_offline`] to work only from a previously-populated cache directory.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::{ReqError, make_agent};
+use crate::{ReqError, make_agent, pdbe, pubchem};
+
+#[cfg(feature = "parse")]
+pub mod parse;
 
 const BASE_URL: &str = "https://www.athanorlab.com";
 
-#[derive(Clone, Debug, Deserialize)]
+/// The maximum number of requests to make at once, in batch loads.
+const MAX_CONCURRENT: usize = 4;
+
+/// How partial charges were derived.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize, Serialize)]
+pub enum ChargeMethod {
+    #[serde(rename = "AM1-BCC")]
+    Am1Bcc,
+    #[serde(rename = "RESP")]
+    Resp,
+    #[serde(other)]
+    Other,
+}
+
+/// The atom type scheme of the parameters.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize, Serialize)]
+pub enum AtomTypeScheme {
+    #[serde(rename = "GAFF")]
+    Gaff,
+    #[serde(rename = "GAFF2")]
+    Gaff2,
+    #[serde(other)]
+    Other,
+}
+
+/// How a molecule was parameterized. Use this to judge whether the parameters are appropriate for
+/// a simulation. Fields are `None` if not reported.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ParamMetadata {
+    pub charge_method: Option<ChargeMethod>,
+    /// In elementary charge units.
+    pub net_charge: Option<i8>,
+    pub atom_types: Option<AtomTypeScheme>,
+    /// E.g. "AMBER_GEOSTD".
+    pub source: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GeostdItem {
     pub ident_pdbe: String,
     pub pubchem_cid: Option<u32>,
     pub frcmod_avail: bool,
     pub lib_avail: bool,
+    #[serde(default, flatten)]
+    pub meta: ParamMetadata,
+}
+
+/// SHA-256 hashes of each file's text, as lowercase hex.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GeostdChecksums {
+    pub mol2: String,
+    pub frcmod: Option<String>,
+    pub lib: Option<String>,
+}
+
+/// The result of checking downloaded files against their checksums.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verification {
+    /// All files match their checksums.
+    Verified,
+    /// At least one file doesn't match its checksum; it may be corrupted, or truncated.
+    Mismatch,
+    /// Checksums weren't provided for all files, so they couldn't all be checked. None that were
+    /// checked mismatched.
+    Unavailable,
 }
 
 /// Contains the text content of these files.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GeostdData {
     pub mol2: String,
     pub frcmod: Option<String>,
     pub lib: Option<String>,
     pub pubchem_cid: Option<u32>,
+    #[serde(default)]
+    pub checksums: Option<GeostdChecksums>,
+    #[serde(default, flatten)]
+    pub meta: ParamMetadata,
+}
+
+fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+impl GeostdData {
+    /// The net charge, from the metadata if reported; otherwise, the sum of the Mol2 file's partial
+    /// charges, rounded.
+    pub fn net_charge(&self) -> Option<i8> {
+        if self.meta.net_charge.is_some() {
+            return self.meta.net_charge;
+        }
+
+        let atoms = self.mol2.split("@<TRIPOS>ATOM").nth(1)?;
+        let atoms = atoms.split("@<TRIPOS>").next()?;
+
+        // The partial charge is the 9th column.
+        let mut total = 0.;
+        for line in atoms.lines().filter(|l| !l.trim().is_empty()) {
+            total += line.split_whitespace().nth(8)?.parse::<f32>().ok()?;
+        }
+
+        Some(total.round() as i8)
+    }
+
+    /// Check each file against its checksum. Note that the server may not provide checksums; this
+    /// is reported as `Verification::Unavailable`, not as verified.
+    pub fn verify(&self) -> Verification {
+        let Some(sums) = &self.checksums else {
+            return Verification::Unavailable;
+        };
+
+        // `None` if the file is present, but its checksum isn't.
+        let matches = |text: &Option<String>, sum: &Option<String>| match (text, sum) {
+            (Some(t), Some(s)) => Some(sha256_hex(t).eq_ignore_ascii_case(s)),
+            (Some(_), None) => None,
+            (None, _) => Some(true),
+        };
+
+        let results = [
+            Some(sha256_hex(&self.mol2).eq_ignore_ascii_case(&sums.mol2)),
+            matches(&self.frcmod, &sums.frcmod),
+            matches(&self.lib, &sums.lib),
+        ];
+
+        if results.contains(&Some(false)) {
+            Verification::Mismatch
+        } else if results.contains(&None) {
+            Verification::Unavailable
+        } else {
+            Verification::Verified
+        }
+    }
+}
+
+static CACHE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+static HOSTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// The cache file name of the list of all molecules. Idents don't start with '_', so this won't
+/// conflict with them.
+const INDEX_FILE: &str = "_index";
+
+/// Set the base URLs to request from, in priority order, e.g. our host, followed by mirrors. If a
+/// request to one fails, the next is tried. An empty list restores the default of our own host.
+pub fn set_hosts(hosts: &[&str]) {
+    *HOSTS.lock().unwrap() = hosts
+        .iter()
+        .map(|h| h.trim_end_matches('/').to_owned())
+        .collect();
+}
+
+fn hosts() -> Vec<String> {
+    let hosts = HOSTS.lock().unwrap().clone();
+    if hosts.is_empty() {
+        vec![BASE_URL.to_owned()]
+    } else {
+        hosts
+    }
+}
+
+/// If true, don't make requests: Load files, and the molecule list from the cache directory only.
+/// This may be populated by a previous session, or copied from another machine. Molecules not in
+/// the cache return `ReqError::NotFound`.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+fn offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Make a request to each host in turn, until one succeeds. Posts `payload` as JSON if present;
+/// otherwise, GETs.
+fn request(path: &str, payload: Option<&str>) -> Result<String, ReqError> {
+    if offline() {
+        return Err(ReqError::Io(io::Error::new(
+            io::ErrorKind::NotConnected,
+            "Amber GeoStd is in offline mode",
+        )));
+    }
+    let agent = make_agent();
+
+    let mut result = Err(ReqError::Http);
+    for host in hosts() {
+        let url = format!("{host}/{path}");
+
+        let resp = match payload {
+            Some(p) => agent
+                .post(url)
+                .header("Content-Type", "application/json")
+                .send(p),
+            None => agent.get(url).call(),
+        };
+
+        match resp {
+            Ok(mut r) if r.status() == 200 => return Ok(r.body_mut().read_to_string()?),
+            Ok(_) => result = Err(ReqError::Http),
+            Err(e) => result = Err(e.into()),
+        }
+    }
+
+    result
+}
+
+/// Set a directory to cache downloaded files in, or `None` to disable caching. When set,
+/// [`load_mol_files`] uses local copies if available, and stores ones it downloads. The directory
+/// is created if it doesn't exist.
+pub fn set_cache_dir(dir: Option<&Path>) {
+    *CACHE_DIR.lock().unwrap() = dir.map(|d| d.to_owned());
+}
+
+/// The cache file path for an ident, or the index, if caching is enabled.
+fn cache_path(ident: &str) -> Option<PathBuf> {
+    let dir = CACHE_DIR.lock().unwrap().clone()?;
+
+    // Idents are short alphanumeric codes. We don't cache anything else, rather than stripping
+    // characters: That could map distinct idents to the same file, or let them escape the directory.
+    let valid = !ident.is_empty()
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return None;
+    }
+
+    Some(dir.join(format!("{ident}.json")))
+}
+
+/// Local copies that fail verification are ignored, so they're downloaded again. Ones without
+/// checksums can't be checked, so are used as-is.
+fn read_cache(ident: &str) -> Option<GeostdData> {
+    let text = fs::read_to_string(cache_path(ident)?).ok()?;
+    let data: GeostdData = serde_json::from_str(&text).ok()?;
+
+    (data.verify() != Verification::Mismatch).then_some(data)
+}
+
+/// Failing to cache doesn't affect the result, so we ignore errors here.
+fn write_cache(ident: &str, data: &GeostdData) {
+    let Some(path) = cache_path(ident) else {
+        return;
+    };
+    let Ok(text) = serde_json::to_string(data) else {
+        return;
+    };
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(path, text);
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -34,56 +295,297 @@ struct GeostdItemResponse {
     pub result: Vec<GeostdItem>,
 }
 
+/// Load the molecule list from the cache, for offline use.
+fn read_index() -> Result<Vec<GeostdItem>, ReqError> {
+    let not_found = || ReqError::NotFound {
+        ident: INDEX_FILE.to_owned(),
+    };
+
+    let text = fs::read_to_string(cache_path(INDEX_FILE).ok_or_else(not_found)?)
+        .map_err(|_| not_found())?;
+    Ok(serde_json::from_str(&text)?)
+}
+
 /// Get a list of all molecules available from Amber Geostd, and if they include FRCMOD and lib data.
 pub fn get_all_mols() -> Result<Vec<GeostdItem>, ReqError> {
-    let agent = make_agent();
-
-    let url = format!("{BASE_URL}/get-all-mols");
-    let resp = agent.get(url).call()?.body_mut().read_to_string()?;
+    if offline() {
+        return read_index();
+    }
 
+    let resp = request("get-all-mols", None)?;
     let parsed: GeostdItemResponse = serde_json::from_str(&resp)?;
+
+    // Store the list for offline use. As with files, this doesn't affect the result.
+    if let (Some(path), Ok(text)) = (
+        cache_path(INDEX_FILE),
+        serde_json::to_string(&parsed.result),
+    ) {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(path, text);
+    }
+
     Ok(parsed.result)
 }
 
-/// Search for molecules by keyword, and find if they include FRCMOD and lib data.
+/// Criteria for [`get_mols`]. The default includes all molecules.
+#[derive(Clone, Debug, Default)]
+pub struct GeostdFilter {
+    /// Only include molecules with FRCMOD data.
+    pub frcmod_required: bool,
+    /// Only include molecules with Lib data.
+    pub lib_required: bool,
+    /// Only include molecules whose ident starts with this. Case-insensitive.
+    pub ident_prefix: Option<String>,
+}
+
+impl GeostdFilter {
+    pub fn matches(&self, item: &GeostdItem) -> bool {
+        if self.frcmod_required && !item.frcmod_avail {
+            return false;
+        }
+        if self.lib_required && !item.lib_avail {
+            return false;
+        }
+        if let Some(prefix) = &self.ident_prefix {
+            let ident = item.ident_pdbe.to_uppercase();
+            if !ident.starts_with(&prefix.to_uppercase()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GeostdPage {
+    pub items: Vec<GeostdItem>,
+    /// The number of molecules matching the filter, across all pages.
+    pub total: usize,
+}
+
+/// Get a page of the molecules matching a filter, sorted by ident. `start` is the index of the first
+/// item to return. Use this to populate lists lazily. Note: The filtering and paging are done
+/// locally; the full list is downloaded each call.
+pub fn get_mols(filter: &GeostdFilter, start: usize, count: usize) -> Result<GeostdPage, ReqError> {
+    let mut items: Vec<_> = get_all_mols()?
+        .into_iter()
+        .filter(|item| filter.matches(item))
+        .collect();
+    items.sort_by(|a, b| a.ident_pdbe.cmp(&b.ident_pdbe));
+
+    let total = items.len();
+
+    Ok(GeostdPage {
+        items: items.into_iter().skip(start).take(count).collect(),
+        total,
+    })
+}
+
+/// Search for molecules by keyword, and find if they include FRCMOD and lib data. Offline, this
+/// only matches idents.
 pub fn find_mols(search_text: &str) -> Result<Vec<GeostdItem>, ReqError> {
-    let agent = make_agent();
+    if offline() {
+        let search_text = search_text.to_uppercase();
+        return Ok(read_index()?
+            .into_iter()
+            .filter(|item| item.ident_pdbe.to_uppercase().contains(&search_text))
+            .collect());
+    }
 
     let mut params = HashMap::new();
     params.insert("search_text", search_text);
     let payload_json = serde_json::to_string(&params)?;
 
-    let url = format!("{BASE_URL}/find-mols");
-
-    let resp = agent
-        .post(url)
-        .header("Content-Type", "application/json")
-        .send(&payload_json)?
-        .body_mut()
-        .read_to_string()?;
+    let resp = request("find-mols", Some(&payload_json))?;
 
     let parsed: GeostdItemResponse = serde_json::from_str(&resp)?;
     Ok(parsed.result)
 }
 
-/// Download a Mol2 file's text, and if available, FRCMOD and Lib, as well as metadata
+/// The result of resolving an identifier with [`resolve_ident`].
+#[derive(Clone, Debug)]
+pub struct GeostdIdent {
+    /// The identifier as passed.
+    pub input: String,
+    /// The PDBe Chemical Component Dictionary code, e.g. "ATP".
+    pub ccd: Option<String>,
+    pub pubchem_cid: Option<u32>,
+    /// The matching molecule in the GeoStd collection, if there is one. Use its `ident_pdbe` to
+    /// load files.
+    pub item: Option<GeostdItem>,
+}
+
+/// Find the PubChem CID of a CCD code, from the InChIKey in its PDBe compound summary. Returns
+/// `ReqError::NotFound` if the code isn't in the CCD, and `None` if it is, but isn't in PubChem.
+fn cid_from_ccd(code: &str) -> Result<Option<u32>, ReqError> {
+    let compound = pdbe::get_compound(code)?;
+
+    Ok(compound
+        .inchi_key
+        .and_then(|key| pubchem::cid_from_inchikey(&key).ok()))
+}
+
+/// Resolve an identifier to its GeoStd entry, cross-referencing PubChem and the PDBe CCD. `input`
+/// can be a PubChem CID (e.g. "5957"), a CCD code (e.g. "ATP"), or a compound name. Returns the
+/// mapping found, even if the molecule isn't in the collection. Numeric inputs are treated as CIDs.
+/// Short alphanumeric inputs are tried as CCD codes, then as names if not in the CCD.
+pub fn resolve_ident(input: &str) -> Result<GeostdIdent, ReqError> {
+    let input = input.trim();
+    let items = get_all_mols()?;
+
+    let by_cid = |cid: u32| items.iter().find(|i| i.pubchem_cid == Some(cid)).cloned();
+
+    let from_cid = |cid: u32| {
+        let item = by_cid(cid);
+        (item.as_ref().map(|i| i.ident_pdbe.clone()), Some(cid), item)
+    };
+
+    let from_name = || -> Result<_, ReqError> {
+        let cid = pubchem::find_cids_by_name(input, pubchem::NameType::Complete)?
+            .first()
+            .copied()
+            .ok_or_else(|| ReqError::NotFound {
+                ident: input.to_owned(),
+            })?;
+        Ok(from_cid(cid))
+    };
+
+    let (ccd, pubchem_cid, item) = if let Ok(cid) = input.parse::<u32>() {
+        from_cid(cid)
+    } else if input.len() <= 5 && input.chars().all(|c| c.is_ascii_alphanumeric()) {
+        let code = input.to_uppercase();
+
+        match items
+            .iter()
+            .find(|i| i.ident_pdbe.eq_ignore_ascii_case(&code))
+        {
+            Some(item) => {
+                let cid = item
+                    .pubchem_cid
+                    .or_else(|| cid_from_ccd(&code).ok().flatten());
+                (Some(code), cid, Some(item.clone()))
+            }
+            // The collection may have the same molecule under a different code.
+            None => match cid_from_ccd(&code) {
+                Ok(cid) => (Some(code), cid, cid.and_then(by_cid)),
+                // Not a CCD code; e.g. a short name like "urea".
+                Err(ReqError::NotFound { .. }) => from_name()?,
+                Err(e) => return Err(e),
+            },
+        }
+    } else {
+        from_name()?
+    };
+
+    Ok(GeostdIdent {
+        input: input.to_owned(),
+        ccd,
+        pubchem_cid,
+        item,
+    })
+}
+
+/// A structure to search the collection for.
+#[derive(Clone, Debug)]
+pub enum StructureQuery {
+    Smiles(String),
+    /// E.g. "BSYNRYMUTXBXSQ-UHFFFAOYSA-N".
+    InchiKey(String),
+}
+
+/// Find molecules in the collection by structure, e.g. to find if parameters exist for a custom
+/// ligand. If `similarity_threshold` is `None`, only exact matches are returned. Otherwise, it's the
+/// minimum Tanimoto similarity (0 - 100), and results are ordered by similarity. Structures are
+/// matched using PubChem, so only molecules with a PubChem CID can be found.
+pub fn find_mols_by_structure(
+    query: &StructureQuery,
+    similarity_threshold: Option<u8>,
+) -> Result<Vec<GeostdItem>, ReqError> {
+    let cids = match (query, similarity_threshold) {
+        (StructureQuery::Smiles(smiles), None) => pubchem::find_cids_from_search(smiles, true)?,
+        (StructureQuery::Smiles(smiles), Some(threshold)) => {
+            pubchem::find_similar_mols_smiles(smiles, threshold)?
+        }
+        (StructureQuery::InchiKey(key), None) => vec![pubchem::cid_from_inchikey(key)?],
+        (StructureQuery::InchiKey(key), Some(threshold)) => {
+            let cid = pubchem::cid_from_inchikey(key)?;
+            let smiles = pubchem::get_smiles_full(cid)?;
+            pubchem::find_similar_mols_smiles(&smiles.isomeric, threshold)?
+        }
+    };
+
+    let mut by_cid: HashMap<u32, Vec<GeostdItem>> = HashMap::new();
+    for item in get_all_mols()? {
+        if let Some(cid) = item.pubchem_cid {
+            by_cid.entry(cid).or_default().push(item);
+        }
+    }
+
+    Ok(cids
+        .iter()
+        .filter_map(|cid| by_cid.remove(cid))
+        .flatten()
+        .collect())
+}
+
+/// Download a Mol2 file's text, and if available, FRCMOD and Lib, as well as metadata. Uses the
+/// local copy instead, if caching is enabled, and one is available. Returns
+/// `ReqError::ChecksumMismatch` if the files don't match their checksums.
 pub fn load_mol_files(ident: &str) -> Result<GeostdData, ReqError> {
-    let agent = make_agent();
+    if let Some(data) = read_cache(ident) {
+        return Ok(data);
+    }
+    if offline() {
+        return Err(ReqError::NotFound {
+            ident: ident.to_owned(),
+        });
+    }
 
+    refresh_mol_files(ident)
+}
+
+/// Like [`load_mol_files`], but always downloads, replacing the local copy if caching is enabled.
+pub fn refresh_mol_files(ident: &str) -> Result<GeostdData, ReqError> {
     let mut params = HashMap::new();
     params.insert("ident", ident);
     let payload_json = serde_json::to_string(&params)?;
 
-    let url = format!("{BASE_URL}/load-mol-files");
+    let resp = request("load-mol-files", Some(&payload_json))?;
+
+    let data: GeostdData = serde_json::from_str(&resp)?;
+    if data.verify() == Verification::Mismatch {
+        return Err(ReqError::ChecksumMismatch {
+            ident: ident.to_owned(),
+        });
+    }
+    write_cache(ident, &data);
+
+    Ok(data)
+}
+
+/// Download files for multiple molecules, e.g. all ligands of a simulation. Requests are made
+/// concurrently, a few at a time. Returns a result for each ident, so one failure doesn't prevent
+/// loading the others.
+pub fn load_mol_files_batch(idents: &[&str]) -> HashMap<String, Result<GeostdData, ReqError>> {
+    let mut result = HashMap::new();
+
+    for chunk in idents.chunks(MAX_CONCURRENT) {
+        thread::scope(|s| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|ident| (ident, s.spawn(|| load_mol_files(ident))))
+                .collect();
 
-    let resp = agent
-        .post(url)
-        .header("Content-Type", "application/json")
-        .send(&payload_json)?
-        .body_mut()
-        .read_to_string()?;
+            for (ident, handle) in handles {
+                let data = handle.join().unwrap_or(Err(ReqError::Http));
+                result.insert(ident.to_string(), data);
+            }
+        });
+    }
 
-    Ok(serde_json::from_str(&resp)?)
+    result
 }
 
 /// Download a Mol2 file from our Amber Geostd database, returning an Mol2 string.
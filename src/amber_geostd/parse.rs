@@ -0,0 +1,454 @@
+//! Parse the text of GeoStd files into structured types: Mol2 atoms and bonds, FRCMOD force field
+//! parameters, and the atoms and connectivity of OFF (lib) residue libraries.
+//!
+//! Enabled with the `parse` feature.
+
+use crate::{
+    ReqError,
+    amber_geostd::{GeostdData, load_mol_files},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mol2Atom {
+    /// 1-based, as in the file.
+    pub id: u32,
+    /// E.g. "C1".
+    pub name: String,
+    /// In Å.
+    pub posit: [f64; 3],
+    /// The GAFF2 atom type, e.g. "c3".
+    pub atom_type: String,
+    pub subst_id: Option<u32>,
+    pub subst_name: Option<String>,
+    pub partial_charge: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mol2Bond {
+    /// 1-based atom IDs.
+    pub atom_0: u32,
+    pub atom_1: u32,
+    /// E.g. "1", "2", "ar", or "am".
+    pub bond_type: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mol2 {
+    pub name: String,
+    pub atoms: Vec<Mol2Atom>,
+    pub bonds: Vec<Mol2Bond>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MassParam {
+    pub atom_type: String,
+    /// In Da.
+    pub mass: f32,
+    /// In Å³.
+    pub polarizability: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BondParam {
+    pub atom_types: [String; 2],
+    /// In kcal/mol/Å².
+    pub k: f32,
+    /// In Å.
+    pub r_0: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AngleParam {
+    pub atom_types: [String; 3],
+    /// In kcal/mol/rad².
+    pub k: f32,
+    /// In degrees.
+    pub theta_0: f32,
+}
+
+/// A proper, or improper dihedral term.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DihedralParam {
+    /// "X" is a wildcard.
+    pub atom_types: [String; 4],
+    /// The factor the barrier is divided by. Always 1 for impropers.
+    pub divider: f32,
+    /// In kcal/mol.
+    pub barrier: f32,
+    /// In degrees.
+    pub phase: f32,
+    /// A negative value indicates more terms follow for the same atom types.
+    pub periodicity: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NonBondedParam {
+    pub atom_type: String,
+    /// In Å.
+    pub r_min_half: f32,
+    /// In kcal/mol.
+    pub epsilon: f32,
+}
+
+/// Force field parameters from a FRCMOD file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Frcmod {
+    pub mass: Vec<MassParam>,
+    pub bonds: Vec<BondParam>,
+    pub angles: Vec<AngleParam>,
+    pub dihedrals: Vec<DihedralParam>,
+    pub impropers: Vec<DihedralParam>,
+    pub nonbonded: Vec<NonBondedParam>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LibAtom {
+    pub name: String,
+    pub atom_type: String,
+    /// Atomic number.
+    pub element: u8,
+    pub charge: f32,
+}
+
+/// A residue template from an OFF (lib) file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lib {
+    pub name: String,
+    pub atoms: Vec<LibAtom>,
+    /// 1-based atom indices.
+    pub bonds: Vec<(u32, u32)>,
+}
+
+/// The parsed forms of each file in a [`GeostdData`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeostdParsed {
+    pub mol2: Mol2,
+    pub frcmod: Option<Frcmod>,
+    pub lib: Option<Lib>,
+}
+
+impl GeostdData {
+    /// Parse the text of each file present. The raw text remains available on `self`.
+    pub fn parse(&self) -> Result<GeostdParsed, ReqError> {
+        Ok(GeostdParsed {
+            mol2: parse_mol2(&self.mol2)?,
+            frcmod: self.frcmod.as_deref().map(parse_frcmod).transpose()?,
+            lib: self.lib.as_deref().map(parse_lib).transpose()?,
+        })
+    }
+}
+
+/// Load the files for a molecule, as with [`load_mol_files`], and parse them. Returns both the raw
+/// text, and the parsed form.
+pub fn load_mol_files_parsed(ident: &str) -> Result<(GeostdData, GeostdParsed), ReqError> {
+    let data = load_mol_files(ident)?;
+    let parsed = data.parse()?;
+
+    Ok((data, parsed))
+}
+
+/// The lines of a Mol2 section, e.g. "ATOM", without the header.
+fn mol2_section<'a>(text: &'a str, name: &str) -> impl Iterator<Item = &'a str> {
+    text.split("@<TRIPOS>")
+        .find(|s| s.starts_with(name))
+        .unwrap_or_default()
+        .lines()
+        .skip(1)
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+}
+
+fn num<T: std::str::FromStr>(v: Option<&str>) -> Result<T, ReqError> {
+    v.and_then(|v| v.parse().ok()).ok_or(ReqError::Deserialize)
+}
+
+pub fn parse_mol2(text: &str) -> Result<Mol2, ReqError> {
+    let name = mol2_section(text, "MOLECULE")
+        .next()
+        .unwrap_or_default()
+        .to_owned();
+
+    let mut atoms = Vec::new();
+    for line in mol2_section(text, "ATOM") {
+        let cols: Vec<_> = line.split_whitespace().collect();
+        if cols.len() < 6 {
+            return Err(ReqError::Deserialize);
+        }
+
+        atoms.push(Mol2Atom {
+            id: num(cols.first().copied())?,
+            name: cols[1].to_owned(),
+            posit: [
+                num(cols.get(2).copied())?,
+                num(cols.get(3).copied())?,
+                num(cols.get(4).copied())?,
+            ],
+            atom_type: cols[5].to_owned(),
+            subst_id: cols.get(6).and_then(|v| v.parse().ok()),
+            subst_name: cols.get(7).map(|v| v.to_string()),
+            partial_charge: cols.get(8).and_then(|v| v.parse().ok()),
+        });
+    }
+
+    let mut bonds = Vec::new();
+    for line in mol2_section(text, "BOND") {
+        let cols: Vec<_> = line.split_whitespace().collect();
+
+        bonds.push(Mol2Bond {
+            atom_0: num(cols.get(1).copied())?,
+            atom_1: num(cols.get(2).copied())?,
+            bond_type: cols.get(3).ok_or(ReqError::Deserialize)?.to_string(),
+        });
+    }
+
+    Ok(Mol2 { name, atoms, bonds })
+}
+
+/// Split a FRCMOD parameter line into its atom types, and the remaining numeric columns. Atom types
+/// are in fixed-width, hyphen-separated columns, and may contain spaces, e.g. "c -o ".
+fn split_types<const N: usize>(line: &str) -> Result<([String; N], Vec<f32>), ReqError> {
+    // Each type is 2 characters, with a hyphen between them.
+    let width = N * 3 - 1;
+    let types_text = line.get(..width).ok_or(ReqError::Deserialize)?;
+
+    let types: Vec<String> = types_text.split('-').map(|t| t.trim().to_owned()).collect();
+    let types: [String; N] = types.try_into().map_err(|_| ReqError::Deserialize)?;
+
+    // Trailing text after the numbers is a comment.
+    let values = line
+        .get(width..)
+        .unwrap_or_default()
+        .split_whitespace()
+        .map_while(|v| v.parse().ok())
+        .collect();
+
+    Ok((types, values))
+}
+
+pub fn parse_frcmod(text: &str) -> Result<Frcmod, ReqError> {
+    let mut result = Frcmod::default();
+    let mut section = "";
+
+    // The first line is a title.
+    for line in text.lines().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let header = line.trim();
+        if ["MASS", "BOND", "ANGLE", "DIHE", "IMPROPER", "NONBON"].contains(&header) {
+            section = header;
+            continue;
+        }
+
+        match section {
+            "MASS" => {
+                let cols: Vec<_> = line.split_whitespace().collect();
+                result.mass.push(MassParam {
+                    atom_type: cols.first().ok_or(ReqError::Deserialize)?.to_string(),
+                    mass: num(cols.get(1).copied())?,
+                    polarizability: cols.get(2).and_then(|v| v.parse().ok()),
+                });
+            }
+            "BOND" => {
+                let (atom_types, v) = split_types::<2>(line)?;
+                result.bonds.push(BondParam {
+                    atom_types,
+                    k: *v.first().ok_or(ReqError::Deserialize)?,
+                    r_0: *v.get(1).ok_or(ReqError::Deserialize)?,
+                });
+            }
+            "ANGLE" => {
+                let (atom_types, v) = split_types::<3>(line)?;
+                result.angles.push(AngleParam {
+                    atom_types,
+                    k: *v.first().ok_or(ReqError::Deserialize)?,
+                    theta_0: *v.get(1).ok_or(ReqError::Deserialize)?,
+                });
+            }
+            "DIHE" => {
+                let (atom_types, v) = split_types::<4>(line)?;
+                if v.len() < 4 {
+                    return Err(ReqError::Deserialize);
+                }
+                result.dihedrals.push(DihedralParam {
+                    atom_types,
+                    divider: v[0],
+                    barrier: v[1],
+                    phase: v[2],
+                    periodicity: v[3],
+                });
+            }
+            "IMPROPER" => {
+                let (atom_types, v) = split_types::<4>(line)?;
+                if v.len() < 3 {
+                    return Err(ReqError::Deserialize);
+                }
+                result.impropers.push(DihedralParam {
+                    atom_types,
+                    divider: 1.,
+                    barrier: v[0],
+                    phase: v[1],
+                    periodicity: v[2],
+                });
+            }
+            "NONBON" => {
+                let cols: Vec<_> = line.split_whitespace().collect();
+                result.nonbonded.push(NonBondedParam {
+                    atom_type: cols.first().ok_or(ReqError::Deserialize)?.to_string(),
+                    r_min_half: num(cols.get(1).copied())?,
+                    epsilon: num(cols.get(2).copied())?,
+                });
+            }
+            _ => (),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Parse the first residue of an OFF (lib) file. Only the atoms, and connectivity tables are read.
+pub fn parse_lib(text: &str) -> Result<Lib, ReqError> {
+    let mut result = Lib::default();
+    let mut table = "";
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if let Some(header) = line.strip_prefix("!entry.") {
+            // E.g. `!entry.ATP.unit.atoms table  str name  str type ...`
+            let mut parts = header.split('.');
+            let name = parts.next().unwrap_or_default();
+            if result.name.is_empty() {
+                result.name = name.to_owned();
+            } else if name != result.name {
+                break;
+            }
+
+            table = parts
+                .nth(1)
+                .unwrap_or_default()
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+            continue;
+        }
+        if line.starts_with('!') {
+            table = "";
+            continue;
+        }
+
+        let cols: Vec<_> = line.split_whitespace().collect();
+        match table {
+            // `"C1" "c3" 0 1 131072 1 6 -0.100000`
+            "atoms" => {
+                if cols.len() < 8 {
+                    return Err(ReqError::Deserialize);
+                }
+                result.atoms.push(LibAtom {
+                    name: cols[0].trim_matches('"').to_owned(),
+                    atom_type: cols[1].trim_matches('"').to_owned(),
+                    element: num(Some(cols[6]))?,
+                    charge: num(Some(cols[7]))?,
+                });
+            }
+            // ` 1 2 1`
+            "connectivity" => {
+                result
+                    .bonds
+                    .push((num(cols.first().copied())?, num(cols.get(1).copied())?));
+            }
+            _ => (),
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mol2() {
+        let text = "@<TRIPOS>MOLECULE
+ACT
+    4     3     1     0     0
+SMALL
+bcc
+
+@<TRIPOS>ATOM
+      1 C1          -0.5030    0.0100    0.0000 c3         1 ACT      -0.214700
+      2 C2           0.9930    0.0200    0.0000 c          1 ACT       0.869600
+      3 O1           1.6130    1.0830    0.0000 o          1 ACT      -0.827400
+      4 O2           1.5670   -1.0950    0.0000 o          1 ACT      -0.827400
+@<TRIPOS>BOND
+     1     1     2 1
+     2     2     3 2
+     3     2     4 1
+";
+        let mol = parse_mol2(text).unwrap();
+
+        assert_eq!(mol.name, "ACT");
+        assert_eq!(mol.atoms.len(), 4);
+        assert_eq!(mol.atoms[1].atom_type, "c");
+        assert_eq!(mol.atoms[2].posit, [1.613, 1.083, 0.]);
+        assert_eq!(mol.atoms[0].partial_charge, Some(-0.2147));
+        assert_eq!(mol.bonds.len(), 3);
+        assert_eq!(mol.bonds[1].bond_type, "2");
+    }
+
+    #[test]
+    fn frcmod() {
+        let text = "Remark line goes here
+MASS
+c3 12.010        0.878
+
+BOND
+c -o   648.00   1.2140       same as c -o
+
+ANGLE
+c3-c -o     68.03     123.110   same as c3-c -o
+
+DIHE
+X -c -c3-X    6    0.000       180.000           3.000      same as X -c -c3-X
+
+IMPROPER
+c3-o -c -o          1.1          180.0         2.0          Using the default value
+
+NONBON
+  c3          1.9080  0.1094             same as c3
+";
+        let params = parse_frcmod(text).unwrap();
+
+        assert_eq!(params.mass[0].mass, 12.01);
+        assert_eq!(params.bonds[0].atom_types, ["c".to_owned(), "o".to_owned()]);
+        assert_eq!(params.bonds[0].r_0, 1.214);
+        assert_eq!(params.angles[0].theta_0, 123.11);
+        assert_eq!(params.dihedrals[0].atom_types[2], "c3");
+        assert_eq!(params.dihedrals[0].divider, 6.);
+        assert_eq!(params.impropers[0].barrier, 1.1);
+        assert_eq!(params.nonbonded[0].epsilon, 0.1094);
+    }
+
+    #[test]
+    fn lib() {
+        let text = r#"!!index array str
+ "ACT"
+!entry.ACT.unit.atoms table  str name  str type  int typex  int resx  int flags  int seq  int elmnt  dbl chg
+ "C1" "c3" 0 1 131072 1 6 -0.214700
+ "C2" "c" 0 1 131072 2 6 0.869600
+!entry.ACT.unit.atomspertinfo table  str pname  str ptype  int ptypex  int pelmnt  dbl pchg
+ "C1" "c3" 0 -1 0.0
+!entry.ACT.unit.connectivity table  int atom1x  int atom2x  int flags
+ 1 2 1
+"#;
+        let lib = parse_lib(text).unwrap();
+
+        assert_eq!(lib.name, "ACT");
+        assert_eq!(lib.atoms.len(), 2);
+        assert_eq!(lib.atoms[1].atom_type, "c");
+        assert_eq!(lib.atoms[0].element, 6);
+        assert_eq!(lib.bonds, vec![(1, 2)]);
+    }
+}
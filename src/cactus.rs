@@ -0,0 +1,30 @@
+//! [Home page](https://cactus.nci.nih.gov/chemical/structure)
+//!
+//! The NCI/CADD Chemical Identifier Resolver. This resolves many identifier types, including
+//! PDBe/Amber ligand identifiers that PubChem doesn't know. We use it as a fallback.
+
+use url::Url;
+
+use crate::{ReqError, make_agent};
+
+const BASE_URL: &str = "https://cactus.nci.nih.gov/chemical/structure";
+
+/// Get the Simplified Molecular Input Line Entry System (SMILES) representation from an identifier.
+/// This seems to work using pdbE/Amber identifiers.
+/// todo: Support SELFEIS too; doesn't seem to be available.
+pub fn get_smiles(ident: &str) -> Result<String, ReqError> {
+    let agent = make_agent();
+    let mut url = Url::parse(BASE_URL).unwrap();
+    // Identifiers may contain spaces, slashes etc; push them as a single encoded segment.
+    url.path_segments_mut().unwrap().push(ident).push("smiles");
+
+    // Make sure to catch the HTTP != 200, and return an error: Otherwise the result will be an OK with
+    // brief HTML failure message string.
+    let mut resp = agent.get(url.as_str()).call()?;
+
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    Ok(resp.body_mut().read_to_string()?.trim().to_owned())
+}
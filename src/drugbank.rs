@@ -1,30 +1,672 @@
 //! [Home page](https://go.drugbank.com/)
 //! [API docs](https://docs.drugbank.com/v1/)
+//!
+//! The REST API functions require an API key, set with [`set_api_key`], or the
+//! `DRUGBANK_API_KEY` environment variable. Without one, [`get_drug`], and [`search_by_name`] use
+//! the open vocabulary if loaded; see the `vocabulary` module.
 
-use crate::{ReqError, make_agent};
+use std::{
+    env,
+    fmt::{Display, Formatter},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_aux::prelude::deserialize_option_number_from_string;
+use url::Url;
+
+use crate::{ReqError, make_agent, pubchem};
+
+pub mod vocabulary;
 
 const BASE_URL: &str = "https://go.drugbank.com/drugs";
 
+const API_URL: &str = "https://api.drugbank.com/v1";
+
+static API_KEY: Mutex<Option<String>> = Mutex::new(None);
+
+static REGION: Mutex<Region> = Mutex::new(Region::Global);
+
+/// The regional dataset to query. This affects products, and their approval status.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Region {
+    /// Data from all regions.
+    #[default]
+    Global,
+    Us,
+    Canada,
+    Eu,
+}
+
+impl Region {
+    fn path(self) -> &'static str {
+        match self {
+            Self::Global => "",
+            Self::Us => "/us",
+            Self::Canada => "/ca",
+            Self::Eu => "/eu",
+        }
+    }
+}
+
+/// Set the DrugBank API key to use, or `None` to remove it.
+pub fn set_api_key(key: Option<&str>) {
+    *API_KEY.lock().unwrap() = key.map(|k| k.to_owned());
+}
+
+/// The API key set with [`set_api_key`], or from the `DRUGBANK_API_KEY` environment variable.
+pub fn api_key() -> Option<String> {
+    if let Some(key) = API_KEY.lock().unwrap().clone() {
+        return Some(key);
+    }
+
+    env::var("DRUGBANK_API_KEY").ok().filter(|k| !k.is_empty())
+}
+
+/// Set the regional dataset to query.
+pub fn set_region(region: Region) {
+    *REGION.lock().unwrap() = region;
+}
+
+/// A DrugBank drug identifier, e.g. "DB00945".
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct DrugBankId(pub String);
+
+impl DrugBankId {
+    pub fn new(id: &str) -> Self {
+        Self(id.trim().to_uppercase())
+    }
+}
+
+impl From<&str> for DrugBankId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl Display for DrugBankId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Make an authenticated GET request to the API. `path` is relative to the regional base URL,
+/// e.g. "drugs/DB00945".
+fn api_get<T: DeserializeOwned>(path: &str, params: &[(&str, &str)]) -> Result<T, ReqError> {
+    let Some(key) = api_key() else {
+        return Err(ReqError::Unauthorized { status: None });
+    };
+    let region = *REGION.lock().unwrap();
+
+    let mut url = Url::parse(&format!("{API_URL}{}/{path}", region.path())).unwrap();
+    {
+        let mut pairs = url.query_pairs_mut();
+        for (k, v) in params {
+            pairs.append_pair(k, v);
+        }
+    }
+
+    let agent = make_agent();
+    let mut resp = agent
+        .get(url.as_str())
+        .header("Authorization", &key)
+        .header("Accept", "application/json")
+        .call()?;
+
+    match resp.status().as_u16() {
+        200 => (),
+        404 => {
+            return Err(ReqError::NotFound {
+                ident: path.to_owned(),
+            });
+        }
+        status @ (401 | 403) => {
+            return Err(ReqError::Unauthorized {
+                status: Some(status),
+            });
+        }
+        _ => return Err(ReqError::Http),
+    }
+
+    Ok(serde_json::from_str(&resp.body_mut().read_to_string()?)?)
+}
+
+/// Properties calculated from the structure.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DrugProperties {
+    pub molecular_formula: Option<String>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub molecular_weight: Option<f32>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub monoisotopic_weight: Option<f32>,
+    pub iupac_name: Option<String>,
+    pub smiles: Option<String>,
+    pub inchi: Option<String>,
+    pub inchikey: Option<String>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub logp: Option<f32>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub polar_surface_area: Option<f32>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub h_bond_donor_count: Option<u8>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub h_bond_acceptor_count: Option<u8>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub rotatable_bond_count: Option<u8>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub physiological_charge: Option<i8>,
+}
+
+/// An identifier of a drug in another database.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ExternalIdentifier {
+    /// E.g. "PubChem Compound", "ChEMBL", "KEGG Drug".
+    pub resource: String,
+    pub identifier: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AtcLevel {
+    /// E.g. "N02BA".
+    pub code: String,
+    /// E.g. "Salicylic acid and derivatives".
+    pub title: String,
+}
+
+/// An Anatomical Therapeutic Chemical classification code.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AtcCode {
+    /// E.g. "N02BA01".
+    pub code: String,
+    /// The parent levels, from most to least specific, e.g. "N02BA", "N02B", "N02", "N".
+    pub levels: Vec<AtcLevel>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Drug {
+    pub drugbank_id: DrugBankId,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub simple_description: Option<String>,
+    #[serde(default)]
+    pub cas_number: Option<String>,
+    #[serde(default)]
+    pub unii: Option<String>,
+    /// E.g. "solid".
+    #[serde(default)]
+    pub state: Option<String>,
+    /// E.g. "approved", "experimental", "withdrawn".
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub external_identifiers: Vec<ExternalIdentifier>,
+    #[serde(default)]
+    pub atc_codes: Vec<AtcCode>,
+    #[serde(default)]
+    pub calculated_properties: Option<DrugProperties>,
+}
+
+/// Load a drug's record, by DrugBank ID. Without an API key, this returns a partial record from
+/// the vocabulary, if loaded.
+pub fn get_drug(id: &DrugBankId) -> Result<Drug, ReqError> {
+    if api_key().is_none()
+        && let Some(drug) = vocabulary::with_vocabulary(|v| v.by_id(id).map(|e| e.to_drug()))
+    {
+        return drug.ok_or(ReqError::NotFound {
+            ident: id.to_string(),
+        });
+    }
+
+    api_get(&format!("drugs/{id}"), &[])
+}
+
+/// A drug, as referenced from another record.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DrugRef {
+    pub drugbank_id: DrugBankId,
+    pub name: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Minor,
+    Moderate,
+    Major,
+    #[serde(other)]
+    Unknown,
+}
+
+/// An interaction between two drugs.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Interaction {
+    /// The drug causing the effect.
+    pub ingredient: DrugRef,
+    /// The drug whose effect is changed.
+    pub affected_ingredient: DrugRef,
+    pub severity: Severity,
+    /// E.g. "increase".
+    #[serde(default)]
+    pub action: Option<String>,
+    pub description: String,
+    #[serde(default)]
+    pub extended_description: Option<String>,
+    /// How to manage the interaction clinically.
+    #[serde(default)]
+    pub management: Option<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct DdiResp {
+    #[serde(default)]
+    interactions: Vec<Interaction>,
+}
+
+/// Find interactions between each pair of the drugs passed.
+pub fn get_ddi(ids: &[DrugBankId]) -> Result<Vec<Interaction>, ReqError> {
+    if ids.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    let ids = ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let resp: DdiResp = api_get("ddi", &[("drugbank_id", &ids)])?;
+    Ok(resp.interactions)
+}
+
+/// The kind of a protein a drug acts on, or is acted on by.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BioEntityKind {
+    Target,
+    Enzyme,
+    Carrier,
+    Transporter,
+}
+
+impl Display for BioEntityKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Target => "targets",
+            Self::Enzyme => "enzymes",
+            Self::Carrier => "carriers",
+            Self::Transporter => "transporters",
+        };
+        write!(f, "{v}")
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Polypeptide {
+    /// The UniProt accession, e.g. "P23219".
+    pub id: String,
+    pub name: String,
+    /// E.g. "Swiss-Prot".
+    pub source: Option<String>,
+    pub gene_name: Option<String>,
+}
+
+/// A target, enzyme, carrier, or transporter of a drug.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BioEntity {
+    /// E.g. "BE0000017".
+    pub id: String,
+    pub name: String,
+    /// E.g. "Humans".
+    pub organism: Option<String>,
+    /// E.g. "inhibitor", "substrate".
+    pub actions: Vec<String>,
+    pub polypeptides: Vec<Polypeptide>,
+}
+
+impl BioEntity {
+    /// UniProt accessions of this entity's proteins. These can be used to find structures of
+    /// it, e.g. with the `rcsb` module.
+    pub fn uniprot_ids(&self) -> Vec<&str> {
+        self.polypeptides.iter().map(|p| p.id.as_str()).collect()
+    }
+}
+
+/// Load the targets, enzymes, carriers, or transporters of a drug.
+pub fn get_bio_entities(id: &DrugBankId, kind: BioEntityKind) -> Result<Vec<BioEntity>, ReqError> {
+    api_get(&format!("drugs/{id}/{kind}"), &[])
+}
+
+/// Load the proteins a drug acts on.
+pub fn get_targets(id: &DrugBankId) -> Result<Vec<BioEntity>, ReqError> {
+    get_bio_entities(id, BioEntityKind::Target)
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Strength {
+    pub number: Option<String>,
+    /// E.g. "mg/1".
+    pub unit: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ProductIngredient {
+    pub drugbank_id: Option<DrugBankId>,
+    pub name: String,
+    pub strength: Option<Strength>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Labeller {
+    pub name: String,
+}
+
+/// A marketed product containing a drug.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Product {
+    pub name: String,
+    /// E.g. "Aspirin 81 mg Oral Tablet, delayed release".
+    pub prescribable_name: Option<String>,
+    pub labeller: Option<Labeller>,
+    /// E.g. "Tablet, delayed release".
+    pub dosage_form: Option<String>,
+    /// E.g. "Oral".
+    pub route: Option<String>,
+    pub ingredients: Vec<ProductIngredient>,
+    /// E.g. "US", "Canada".
+    pub country: Option<String>,
+    pub approved_on: Option<String>,
+    pub generic: Option<bool>,
+    pub otc: Option<bool>,
+}
+
+/// Load the marketed products containing a drug, in the selected region.
+pub fn get_products(id: &DrugBankId) -> Result<Vec<Product>, ReqError> {
+    api_get(&format!("drugs/{id}/products"), &[])
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Condition {
+    /// E.g. "DBCOND0027855".
+    pub drugbank_id: String,
+    /// E.g. "Myocardial Infarction".
+    pub name: String,
+}
+
+/// A use of a drug.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Indication {
+    /// E.g. "used_for_the_treatment_of", "used_for_the_prevention_of".
+    pub kind: String,
+    pub condition: Option<Condition>,
+    pub off_label: bool,
+    pub otc_use: bool,
+    /// If the drug is used in addition to another therapy.
+    pub adjunct_use: bool,
+    /// E.g. "US".
+    pub regions: Option<String>,
+}
+
+/// Load the conditions a drug is used to treat, prevent, or manage.
+pub fn get_indications(id: &DrugBankId) -> Result<Vec<Indication>, ReqError> {
+    api_get(&format!("drugs/{id}/indications"), &[])
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct DrugNameProduct {
+    ingredients: Vec<ProductIngredient>,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct DrugNamesResp {
+    products: Vec<DrugNameProduct>,
+}
+
+/// Search for drugs by name, e.g. a generic name like "acetaminophen", or a brand name like
+/// "Tylenol". Matching is fuzzy, so misspellings are tolerated. If there are no matches, hyphenated
+/// and spaced variants are tried, e.g. "co-codamol" and "co codamol". Returns each matching drug
+/// once, in the order found.
+pub fn search_by_name(query: &str) -> Result<Vec<DrugRef>, ReqError> {
+    let query = query.trim();
+
+    // Without an API key, use exact name and synonym matches from the vocabulary, if loaded.
+    if api_key().is_none()
+        && let Some(matches) = vocabulary::with_vocabulary(|v| {
+            v.by_name(query)
+                .into_iter()
+                .map(|e| DrugRef {
+                    drugbank_id: e.drugbank_id.clone(),
+                    name: e.name.clone(),
+                })
+                .collect()
+        })
+    {
+        return Ok(matches);
+    }
+
+    let mut variants = vec![query.to_owned()];
+    for v in [
+        query.replace('-', " "),
+        query.replace(' ', "-"),
+        query.replace('-', ""),
+    ] {
+        if !variants.contains(&v) {
+            variants.push(v);
+        }
+    }
+
+    let mut result: Vec<DrugRef> = Vec::new();
+    for variant in variants {
+        let resp: DrugNamesResp = api_get("drug_names", &[("q", &variant), ("fuzzy", "true")])?;
+
+        for ingredient in resp.products.into_iter().flat_map(|p| p.ingredients) {
+            let Some(drugbank_id) = ingredient.drugbank_id else {
+                continue;
+            };
+            if !result.iter().any(|r| r.drugbank_id == drugbank_id) {
+                result.push(DrugRef {
+                    drugbank_id,
+                    name: ingredient.name,
+                });
+            }
+        }
+
+        if !result.is_empty() {
+            break;
+        }
+    }
+
+    Ok(result)
+}
+
+/// A database to convert a DrugBank ID to, with [`convert_id`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum IdTarget {
+    PubChemCid,
+    Cas,
+    Chembl,
+    /// FDA Unique Ingredient Identifier.
+    Unii,
+}
+
+impl Drug {
+    /// Identifiers from an external resource, e.g. "ChEMBL".
+    pub fn external_ids(&self, resource: &str) -> Vec<&str> {
+        self.external_identifiers
+            .iter()
+            .filter(|e| e.resource.eq_ignore_ascii_case(resource))
+            .map(|e| e.identifier.as_str())
+            .collect()
+    }
+}
+
+/// Convert a DrugBank ID to the equivalent in another database, e.g. to chain queries with the
+/// `pubchem` module. Uses the drug's external links, or the vocabulary without an API key. In that
+/// case, PubChem CIDs are found from the InChIKey, and ChEMBL IDs aren't available.
+pub fn convert_id(id: &DrugBankId, target: IdTarget) -> Result<String, ReqError> {
+    let drug = get_drug(id)?;
+
+    let result = match target {
+        IdTarget::Cas => drug.cas_number.clone(),
+        IdTarget::Unii => drug.unii.clone(),
+        IdTarget::Chembl => drug.external_ids("ChEMBL").first().map(|v| v.to_string()),
+        IdTarget::PubChemCid => match drug.external_ids("PubChem Compound").first() {
+            Some(cid) => Some(cid.to_string()),
+            None => match drug
+                .calculated_properties
+                .as_ref()
+                .and_then(|p| p.inchikey.as_ref())
+            {
+                Some(key) => Some(pubchem::cid_from_inchikey(key)?.to_string()),
+                None => None,
+            },
+        },
+    };
+
+    result.ok_or(ReqError::NotFound {
+        ident: id.to_string(),
+    })
+}
+
+/// A DrugBank category, e.g. a pharmacological, or chemical class.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DrugCategory {
+    /// E.g. "DBCAT000600".
+    pub drugbank_id: String,
+    /// E.g. "Anti-Inflammatory Agents, Non-Steroidal".
+    pub name: String,
+    pub mesh_id: Option<String>,
+    pub mesh_tree_numbers: Vec<String>,
+    /// Set if this category corresponds to an ATC level.
+    pub atc_code: Option<String>,
+    pub atc_level: Option<u8>,
+    /// E.g. "therapeutic", "pharmacological".
+    pub categorization_kind: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DrugCategories {
+    pub atc_codes: Vec<AtcCode>,
+    pub categories: Vec<DrugCategory>,
+}
+
+/// Load a drug's ATC codes, and DrugBank categories. Use these to group drugs by therapeutic class.
+pub fn get_categories(id: &DrugBankId) -> Result<DrugCategories, ReqError> {
+    let drug = get_drug(id)?;
+    let categories = api_get(&format!("drugs/{id}/categories"), &[])?;
+
+    Ok(DrugCategories {
+        atc_codes: drug.atc_codes,
+        categories,
+    })
+}
+
 pub fn open_overview(id: &str) {
     if let Err(e) = webbrowser::open(&format!("{BASE_URL}/{id}")) {
         eprintln!("Failed to open the web browser: {:?}", e);
     }
 }
 
-fn sdf_url(ident: &str) -> String {
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Dimensionality {
+    D2,
+    D3,
+}
+
+impl Display for Dimensionality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::D2 => "2d",
+            Self::D3 => "3d",
+        };
+        write!(f, "{v}")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StructureFormat {
+    Sdf,
+    Mol,
+}
+
+impl Display for StructureFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Sdf => "sdf",
+            Self::Mol => "mol",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// A structure file, and which representation it is.
+#[derive(Clone, Debug)]
+pub struct DrugStructure {
+    pub text: String,
+    pub format: StructureFormat,
+    /// This may be 2D when 3D was requested, if 3D coordinates aren't available.
+    pub dim: Dimensionality,
+}
+
+fn structure_url(ident: &str, format: StructureFormat, dim: Dimensionality) -> String {
     format!(
-        "https://go.drugbank.com/structures/small_molecule_drugs/{}.sdf?type=3d",
+        "https://go.drugbank.com/structures/small_molecule_drugs/{}.{format}?type={dim}",
         ident.to_uppercase()
     )
 }
 
-/// Download an SDF file from DrugBank, returning an SDF string.
-pub fn load_sdf(ident: &str) -> Result<String, ReqError> {
+/// Download a structure file from DrugBank. If 3D is requested, and the drug doesn't have 3D
+/// coordinates, this falls back to 2D.
+pub fn load_structure(
+    ident: &str,
+    format: StructureFormat,
+    dim: Dimensionality,
+) -> Result<DrugStructure, ReqError> {
     let agent = make_agent();
 
-    Ok(agent
-        .get(sdf_url(ident))
-        .call()?
-        .body_mut()
-        .read_to_string()?)
+    let mut dims = vec![dim];
+    if dim == Dimensionality::D3 {
+        dims.push(Dimensionality::D2);
+    }
+
+    for dim in dims {
+        let mut resp = agent.get(structure_url(ident, format, dim)).call()?;
+        if resp.status() != 200 {
+            continue;
+        }
+
+        let text = resp.body_mut().read_to_string()?;
+        if !text.trim().is_empty() {
+            return Ok(DrugStructure { text, format, dim });
+        }
+    }
+
+    Err(ReqError::NotFound {
+        ident: ident.to_owned(),
+    })
+}
+
+/// Download an SDF file from DrugBank, returning an SDF string. This is 3D if available; otherwise
+/// 2D. Use [`load_structure`] to find which.
+pub fn load_sdf(ident: &str) -> Result<String, ReqError> {
+    Ok(load_structure(ident, StructureFormat::Sdf, Dimensionality::D3)?.text)
 }
@@ -0,0 +1,285 @@
+//! The DrugBank vocabulary: Open data (CC0) mapping DrugBank IDs to names, synonyms, CAS numbers,
+//! UNIIs, and InChIKeys. This doesn't require an API key. Once loaded with [`set_vocabulary`], the
+//! module's functions use it to answer what they can when there's no API key.
+//! [Download page](https://go.drugbank.com/releases/latest#open-data)
+//!
+//! Downloading requires a (free) DrugBank account; set its credentials with [`set_credentials`], or
+//! the `DRUGBANK_EMAIL` and `DRUGBANK_PASSWORD` environment variables.
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, Cursor, Read},
+    path::Path,
+    sync::Mutex,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use zip::ZipArchive;
+
+use crate::{
+    ReqError,
+    drugbank::{Drug, DrugBankId, DrugProperties},
+    make_agent_download, split_csv_line,
+};
+
+const VOCABULARY_URL: &str =
+    "https://go.drugbank.com/releases/latest/downloads/all-drugbank-vocabulary";
+
+static VOCABULARY: Mutex<Option<Vocabulary>> = Mutex::new(None);
+
+/// Email, and password.
+static CREDENTIALS: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+/// Set the DrugBank account credentials (email, and password) used to download the vocabulary.
+/// `None` clears them, falling back to the `DRUGBANK_EMAIL` and `DRUGBANK_PASSWORD` environment
+/// variables.
+pub fn set_credentials(credentials: Option<(&str, &str)>) {
+    *CREDENTIALS.lock().unwrap() = credentials.map(|(e, p)| (e.to_owned(), p.to_owned()));
+}
+
+fn credentials() -> Option<(String, String)> {
+    if let Some(c) = CREDENTIALS.lock().unwrap().clone() {
+        return Some(c);
+    }
+
+    let email = env::var("DRUGBANK_EMAIL").ok().filter(|v| !v.is_empty())?;
+    let password = env::var("DRUGBANK_PASSWORD").ok()?;
+    Some((email, password))
+}
+
+#[derive(Clone, Debug)]
+pub struct VocabEntry {
+    pub drugbank_id: DrugBankId,
+    /// Previous IDs, e.g. "BTD00024".
+    pub accession_numbers: Vec<String>,
+    pub name: String,
+    pub cas: Option<String>,
+    pub unii: Option<String>,
+    pub synonyms: Vec<String>,
+    pub inchikey: Option<String>,
+}
+
+impl VocabEntry {
+    /// A partial drug record, with the fields available from the vocabulary.
+    pub fn to_drug(&self) -> Drug {
+        Drug {
+            drugbank_id: self.drugbank_id.clone(),
+            name: self.name.clone(),
+            description: None,
+            simple_description: None,
+            cas_number: self.cas.clone(),
+            unii: self.unii.clone(),
+            state: None,
+            groups: Vec::new(),
+            external_identifiers: Vec::new(),
+            atc_codes: Vec::new(),
+            calculated_properties: self.inchikey.as_ref().map(|k| DrugProperties {
+                inchikey: Some(k.clone()),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+/// An index of the vocabulary, for lookups by ID, name (including synonyms), CAS number, or
+/// InChIKey.
+#[derive(Clone, Debug, Default)]
+pub struct Vocabulary {
+    pub entries: Vec<VocabEntry>,
+    by_id: HashMap<String, usize>,
+    /// Lowercase names and synonyms.
+    by_name: HashMap<String, Vec<usize>>,
+    by_cas: HashMap<String, usize>,
+    by_inchikey: HashMap<String, usize>,
+}
+
+impl Vocabulary {
+    /// Parse the vocabulary CSV, with columns "DrugBank ID", "Accession Numbers", "Common name",
+    /// "CAS", "UNII", "Synonyms", and "Standard InChI Key".
+    pub fn from_csv(text: &str) -> Self {
+        let mut result = Self::default();
+
+        // Skip the header.
+        for line in text.lines().skip(1).filter(|l| !l.trim().is_empty()) {
+            let cols = split_csv_line(line);
+            if cols.len() < 7 {
+                continue;
+            }
+
+            let optional = |v: &str| {
+                let v = v.trim();
+                (!v.is_empty()).then(|| v.to_owned())
+            };
+            // Multiple values are separated by " | ".
+            let list = |v: &str| -> Vec<String> {
+                v.split('|')
+                    .map(|s| s.trim().to_owned())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            };
+
+            result.push(VocabEntry {
+                drugbank_id: DrugBankId::new(&cols[0]),
+                accession_numbers: list(&cols[1]),
+                name: cols[2].trim().to_owned(),
+                cas: optional(&cols[3]),
+                unii: optional(&cols[4]),
+                synonyms: list(&cols[5]),
+                inchikey: optional(&cols[6]),
+            });
+        }
+
+        result
+    }
+
+    fn push(&mut self, entry: VocabEntry) {
+        let i = self.entries.len();
+
+        self.by_id.insert(entry.drugbank_id.0.clone(), i);
+        for id in &entry.accession_numbers {
+            self.by_id.entry(id.to_uppercase()).or_insert(i);
+        }
+        for name in std::iter::once(&entry.name).chain(&entry.synonyms) {
+            let indices = self.by_name.entry(name.to_lowercase()).or_default();
+            if !indices.contains(&i) {
+                indices.push(i);
+            }
+        }
+        if let Some(cas) = &entry.cas {
+            self.by_cas.insert(cas.clone(), i);
+        }
+        if let Some(key) = &entry.inchikey {
+            self.by_inchikey.insert(key.to_uppercase(), i);
+        }
+
+        self.entries.push(entry);
+    }
+
+    /// Download the latest vocabulary from DrugBank, and index it. Requires account credentials;
+    /// see [`set_credentials`].
+    pub fn download() -> Result<Self, ReqError> {
+        let Some((email, password)) = credentials() else {
+            return Err(ReqError::Unauthorized { status: None });
+        };
+        let auth = STANDARD.encode(format!("{email}:{password}"));
+
+        let agent = make_agent_download();
+
+        let resp = agent
+            .get(VOCABULARY_URL)
+            .header("Authorization", &format!("Basic {auth}"))
+            .call()?;
+        if resp.status() == 401 || resp.status() == 403 {
+            return Err(ReqError::Unauthorized {
+                status: Some(resp.status().as_u16()),
+            });
+        }
+        if resp.status() != 200 {
+            return Err(ReqError::Http);
+        }
+        let mut zipped = Vec::new();
+        resp.into_body().into_reader().read_to_end(&mut zipped)?;
+
+        let csv = String::from_utf8(unzip_first(&zipped)?).map_err(|_| ReqError::Deserialize)?;
+        Ok(Self::from_csv(&csv))
+    }
+
+    /// Load the vocabulary from a CSV file, e.g. one downloaded previously.
+    pub fn load(path: &Path) -> Result<Self, ReqError> {
+        Ok(Self::from_csv(&fs::read_to_string(path)?))
+    }
+
+    /// Look up a drug by DrugBank ID, including previous accession numbers.
+    pub fn by_id(&self, id: &DrugBankId) -> Option<&VocabEntry> {
+        self.by_id.get(&id.0).map(|&i| &self.entries[i])
+    }
+
+    /// Look up drugs by name or synonym. Case-insensitive.
+    pub fn by_name(&self, name: &str) -> Vec<&VocabEntry> {
+        self.by_name
+            .get(&name.trim().to_lowercase())
+            .map(|indices| indices.iter().map(|&i| &self.entries[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Look up a drug by CAS number, e.g. "50-78-2".
+    pub fn by_cas(&self, cas: &str) -> Option<&VocabEntry> {
+        self.by_cas.get(cas.trim()).map(|&i| &self.entries[i])
+    }
+
+    /// Look up a drug by InChIKey, e.g. "BSYNRYMUTXBXSQ-UHFFFAOYSA-N".
+    pub fn by_inchikey(&self, inchikey: &str) -> Option<&VocabEntry> {
+        self.by_inchikey
+            .get(&inchikey.trim().to_uppercase())
+            .map(|&i| &self.entries[i])
+    }
+}
+
+/// Extract the first file of a ZIP archive. The vocabulary download contains a single CSV.
+fn unzip_first(data: &[u8]) -> Result<Vec<u8>, ReqError> {
+    // E.g. if we were served a login page instead of the archive.
+    let invalid = |e: zip::result::ZipError| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("Invalid ZIP file: {e}"))
+    };
+
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(invalid)?;
+    let mut file = archive.by_index(0).map_err(invalid)?;
+
+    let mut result = Vec::new();
+    file.read_to_end(&mut result)?;
+
+    Ok(result)
+}
+
+/// Set the vocabulary to use for lookups without an API key, or `None` to clear it.
+pub fn set_vocabulary(vocabulary: Option<Vocabulary>) {
+    *VOCABULARY.lock().unwrap() = vocabulary;
+}
+
+/// Run a lookup on the vocabulary, if one is set.
+pub(crate) fn with_vocabulary<T>(f: impl FnOnce(&Vocabulary) -> T) -> Option<T> {
+    VOCABULARY.lock().unwrap().as_ref().map(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"DrugBank ID,Accession Numbers,Common name,CAS,UNII,Synonyms,Standard InChI Key
+DB00001,BTD00024 | BIOD00024,Lepirudin,138068-37-8,Y43GF64R34,"Hirudin variant-1, recombinant | Lepirudin recombinant",
+DB00316,APRD00252,Acetaminophen,103-90-2,362O9ITL9D,Paracetamol | APAP,RZVAJINKPMORJF-UHFFFAOYSA-N
+
+DB99999,,Incomplete
+"#;
+
+    #[test]
+    fn from_csv() {
+        let vocab = Vocabulary::from_csv(SAMPLE);
+        assert_eq!(vocab.entries.len(), 2);
+
+        let lepirudin = &vocab.entries[0];
+        assert_eq!(lepirudin.accession_numbers, ["BTD00024", "BIOD00024"]);
+        assert_eq!(
+            lepirudin.synonyms,
+            ["Hirudin variant-1, recombinant", "Lepirudin recombinant"]
+        );
+        assert_eq!(lepirudin.inchikey, None);
+
+        let id = DrugBankId::new("db00316");
+        assert_eq!(vocab.by_id(&id).unwrap().name, "Acetaminophen");
+        assert_eq!(
+            vocab.by_id(&DrugBankId::new("btd00024")).unwrap().name,
+            "Lepirudin"
+        );
+        assert_eq!(vocab.by_name("paracetamol")[0].drugbank_id, id);
+        assert_eq!(vocab.by_cas("103-90-2").unwrap().drugbank_id, id);
+        assert_eq!(
+            vocab
+                .by_inchikey("rzvajinkpmorjf-uhfffaoysa-n")
+                .unwrap()
+                .drugbank_id,
+            id
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::{io, time::Duration};
 use ureq::Agent;
 
 pub mod amber_geostd;
+pub mod cactus;
 pub mod drugbank;
 pub mod lmsd;
 pub mod ncbi;
@@ -14,9 +15,24 @@ pub mod rcsb;
 #[derive(Debug)]
 pub enum ReqError {
     Http,
+    /// The identifier queried doesn't exist in the database.
+    NotFound {
+        ident: String,
+    },
     Ser(serde_json::Error),
     Deserialize,
     Io(io::Error),
+    /// Downloaded data doesn't match its checksum; it may be corrupted, or truncated.
+    ChecksumMismatch {
+        ident: String,
+    },
+    /// Credentials, e.g. an API key, are missing, or were rejected. `status` is the HTTP status
+    /// code, if a request was made.
+    Unauthorized {
+        status: Option<u16>,
+    },
+    /// An asynchronous job, e.g. a PubChem ListKey search, isn't complete yet.
+    Pending,
 }
 
 impl From<ureq::Error> for ReqError {
@@ -38,6 +54,8 @@ impl From<io::Error> for ReqError {
 }
 
 const HTTP_TIMEOUT: u64 = 5; // In seconds
+// For large file downloads, we only limit the time to connect; the transfer may take a while.
+const HTTP_TIMEOUT_CONNECT: u64 = 10; // In seconds
 
 fn make_agent() -> Agent {
     let config = Agent::config_builder()
@@ -48,3 +66,53 @@ fn make_agent() -> Agent {
 
     config.into()
 }
+
+/// For downloading large files, e.g. full dictionaries, where the transfer may take longer than
+/// `HTTP_TIMEOUT`.
+fn make_agent_download() -> Agent {
+    let config = Agent::config_builder()
+        .timeout_connect(Some(Duration::from_secs(HTTP_TIMEOUT_CONNECT)))
+        .http_status_as_error(false)
+        .build();
+
+    config.into()
+}
+
+/// Split a CSV line, respecting quoted fields.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => result.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    result.push(field);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_quoting() {
+        assert_eq!(split_csv_line("a,b,,c"), ["a", "b", "", "c"]);
+        assert_eq!(
+            split_csv_line(r#"DB00001,"Lepirudin, recombinant","He said ""hi""",x"#),
+            ["DB00001", "Lepirudin, recombinant", r#"He said "hi""#, "x"]
+        );
+        assert_eq!(split_csv_line(r#""""#), [""]);
+        assert_eq!(split_csv_line("a,"), ["a", ""]);
+    }
+}
@@ -1,8 +1,191 @@
 //! [Home page](https://www.lipidmaps.org/lmsd_search/30105))
+//! [REST API docs](https://www.lipidmaps.org/resources/rest)
 
-use crate::{ReqError, make_agent};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_aux::prelude::deserialize_option_number_from_string;
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    ReqError, make_agent,
+    pubchem::{self, NameType},
+};
 
 const BASE_URL: &str = "https://www.lipidmaps.org/databases/lmsd";
+const REST_URL: &str = "https://www.lipidmaps.org/rest";
+
+/// A compound record from the LIPID MAPS REST API.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Lipid {
+    /// E.g. "LMFA01030001".
+    pub lm_id: String,
+    pub name: Option<String>,
+    pub sys_name: Option<String>,
+    /// E.g. "PC 34:1".
+    pub abbrev: Option<String>,
+    /// E.g. "PC 16:0_18:1".
+    pub abbrev_chains: Option<String>,
+    pub formula: Option<String>,
+    #[serde(
+        rename = "exactmass",
+        deserialize_with = "deserialize_option_number_from_string"
+    )]
+    pub exact_mass: Option<f64>,
+    pub inchi_key: Option<String>,
+    pub smiles: Option<String>,
+    /// Classification, from broadest to narrowest. E.g. "Fatty Acyls [FA]".
+    pub core: Option<String>,
+    pub main_class: Option<String>,
+    pub sub_class: Option<String>,
+    pub class_level4: Option<String>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub pubchem_cid: Option<u32>,
+    pub chebi_id: Option<String>,
+    pub hmdb_id: Option<String>,
+    pub kegg_id: Option<String>,
+}
+
+/// Make a REST API request, with each path segment encoded. E.g. `["compound", "abbrev",
+/// "PC 34:1", "all", "json"]`.
+fn rest_get(segments: &[&str]) -> Result<String, ReqError> {
+    let mut url = Url::parse(REST_URL).unwrap();
+    url.path_segments_mut().unwrap().extend(segments);
+
+    let agent = make_agent();
+    let mut resp = agent.get(url.as_str()).call()?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    Ok(resp.body_mut().read_to_string()?)
+}
+
+/// Responses are a single record if there's one match, an object of "Row1", "Row2" etc if several,
+/// and empty, or an empty array if none. `single_key` is a field present in a single record.
+/// Missing values are reported as "-", or empty strings; we treat these as absent.
+fn parse_rows<T: DeserializeOwned>(text: &str, single_key: &str) -> Result<Vec<T>, ReqError> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let records = match serde_json::from_str(text)? {
+        Value::Array(v) => v,
+        Value::Object(map) if map.contains_key(single_key) => vec![Value::Object(map)],
+        Value::Object(map) => {
+            // Order by row number; not lexically, which would put "Row10" before "Row2".
+            let mut rows: Vec<_> = map
+                .into_iter()
+                .map(|(k, v)| {
+                    let i: u32 = k.trim_start_matches("Row").parse().unwrap_or(u32::MAX);
+                    (i, v)
+                })
+                .collect();
+            rows.sort_by_key(|(i, _)| *i);
+            rows.into_iter().map(|(_, v)| v).collect()
+        }
+        _ => return Err(ReqError::Deserialize),
+    };
+
+    Ok(records
+        .into_iter()
+        .map(|mut record| {
+            if let Value::Object(map) = &mut record {
+                for v in map.values_mut() {
+                    if matches!(v.as_str(), Some("-" | "")) {
+                        *v = Value::Null;
+                    }
+                }
+            }
+            serde_json::from_value(record)
+        })
+        .collect::<Result<Vec<T>, _>>()?)
+}
+
+/// Query the compound context of the REST API, e.g. `("formula", "C20H32O2")`, returning all fields.
+fn rest_compound(input_item: &str, input_value: &str) -> Result<Vec<Lipid>, ReqError> {
+    let text = rest_get(&["compound", input_item, input_value, "all", "json"])?;
+    parse_rows(&text, "lm_id")
+}
+
+/// The maximum number of PubChem compounds to look up in LMSD, when searching by name.
+const MAX_NAME_CIDS: usize = 5;
+
+/// Search for lipids by name or abbreviation, e.g. "Arachidonic acid", "PC 34:1", or
+/// "PC 16:0_18:1". Tries the species-level abbreviation, then the chain-level one. The REST API
+/// has no name query, so names are then resolved to CIDs using PubChem, and looked up by those.
+pub fn search(name_or_abbrev: &str) -> Result<Vec<Lipid>, ReqError> {
+    let query = name_or_abbrev.trim();
+
+    for input_item in ["abbrev", "abbrev_chains"] {
+        let result = rest_compound(input_item, query)?;
+        if !result.is_empty() {
+            return Ok(result);
+        }
+    }
+
+    let cids = match pubchem::find_cids_by_name(query, NameType::Complete) {
+        Ok(c) => c,
+        Err(ReqError::NotFound { .. }) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut result: Vec<Lipid> = Vec::new();
+    for cid in cids.into_iter().take(MAX_NAME_CIDS) {
+        for lipid in rest_compound("pubchem_cid", &cid.to_string())? {
+            if !result.iter().any(|l| l.lm_id == lipid.lm_id) {
+                result.push(lipid);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Find lipids with a given molecular formula, e.g. "C20H32O2".
+pub fn search_by_formula(formula: &str) -> Result<Vec<Lipid>, ReqError> {
+    rest_compound("formula", formula.trim())
+}
+
+/// Find a lipid by its LM ID, e.g. "LMFA01030001".
+pub fn get_lipid(lm_id: &str) -> Result<Lipid, ReqError> {
+    let ident = lm_id.trim().to_uppercase();
+
+    rest_compound("lm_id", &ident)?
+        .into_iter()
+        .next()
+        .ok_or(ReqError::NotFound { ident })
+}
+
+/// A match from a mass search.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct MassMatch {
+    /// The m/z queried.
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub input: Option<f64>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub mass: Option<f64>,
+    pub name: Option<String>,
+    pub formula: Option<String>,
+    /// The difference between the queried and matched m/z.
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub delta: Option<f64>,
+}
+
+/// Find lipids matching a mass, e.g. from mass spectrometry. `ion` is an adduct, e.g. "M+H",
+/// "M+Na", or "M-H". `tolerance` is in Da.
+pub fn search_by_mass(mz: f64, ion: &str, tolerance: f64) -> Result<Vec<MassMatch>, ReqError> {
+    let text = rest_get(&[
+        "moverz",
+        "LIPIDS",
+        &mz.to_string(),
+        ion,
+        &tolerance.to_string(),
+        "json",
+    ])?;
+    parse_rows(&text, "name")
+}
 
 pub fn open_overview(ident: &str) {
     if let Err(e) = webbrowser::open(&format!("{BASE_URL}/{}", ident.to_uppercase())) {
@@ -25,3 +208,59 @@ pub fn load_sdf(ident: &str) -> Result<String, ReqError> {
         .body_mut()
         .read_to_string()?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_row() {
+        let text = r#"{"lm_id":"LMFA01030001","name":"Arachidonic acid","abbrev":"FA 20:4",
+            "exactmass":"304.2402","pubchem_cid":"444899","chebi_id":"-","kegg_id":""}"#;
+
+        let lipids: Vec<Lipid> = parse_rows(text, "lm_id").unwrap();
+        assert_eq!(lipids.len(), 1);
+        assert_eq!(lipids[0].lm_id, "LMFA01030001");
+        assert_eq!(lipids[0].exact_mass, Some(304.2402));
+        assert_eq!(lipids[0].pubchem_cid, Some(444899));
+        assert_eq!(lipids[0].chebi_id, None);
+        assert_eq!(lipids[0].kegg_id, None);
+    }
+
+    #[test]
+    fn row_order() {
+        // Rows are numbered from 1; "Row10" must sort after "Row9", not after "Row1".
+        let rows: Vec<_> = (1..=11)
+            .map(|i| format!(r#""Row{i}":{{"lm_id":"LM{i}","pubchem_cid":"-"}}"#))
+            .collect();
+        let text = format!("{{{}}}", rows.join(","));
+
+        let lipids: Vec<Lipid> = parse_rows(&text, "lm_id").unwrap();
+        let ids: Vec<_> = lipids.iter().map(|l| l.lm_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "LM1", "LM2", "LM3", "LM4", "LM5", "LM6", "LM7", "LM8", "LM9", "LM10", "LM11"
+            ]
+        );
+        assert!(lipids.iter().all(|l| l.pubchem_cid.is_none()));
+    }
+
+    #[test]
+    fn mass_matches() {
+        let text = r#"{"Row1":{"input":"760.585","mass":"760.5851","name":"PC 34:1","formula":"C42H83NO8P","delta":"0.0001"},
+            "Row2":{"input":"760.585","mass":"760.5851","name":"PE 37:1","formula":"C42H83NO8P","delta":"-"}}"#;
+
+        let matches: Vec<MassMatch> = parse_rows(text, "name").unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].name.as_deref(), Some("PC 34:1"));
+        assert_eq!(matches[0].delta, Some(0.0001));
+        assert_eq!(matches[1].delta, None);
+    }
+
+    #[test]
+    fn empty() {
+        assert!(parse_rows::<Lipid>("", "lm_id").unwrap().is_empty());
+        assert!(parse_rows::<Lipid>("[]", "lm_id").unwrap().is_empty());
+    }
+}
@@ -1,8 +1,1915 @@
-use na_seq::{Nucleotide, seq_to_str_lower};
+//! [Home page](https://www.ncbi.nlm.nih.gov/)
+//! [E-utilities docs](https://www.ncbi.nlm.nih.gov/books/NBK25501/)
+//!
+//! This includes the Entrez E-utilities, for querying NCBI databases, and BLAST. See the
+//! `datasets` module for genome assemblies and gene data packages.
+//!
+//! Requests are throttled to NCBI's limit of 3 per second, or 10 per second with an API key. Set
+//! one with [`set_api_key`], or the `NCBI_API_KEY` environment variable.
+
+use std::{
+    env,
+    fmt::{Display, Formatter},
+    io,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use na_seq::{AminoAcid, Nucleotide, seq_aa_from_str, seq_to_str_lower};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_aux::prelude::deserialize_number_from_string;
+use ureq::Agent;
 use url::Url;
 
+use crate::{HTTP_TIMEOUT, HTTP_TIMEOUT_CONNECT, ReqError, rcsb::Citation, split_csv_line};
+
+pub mod datasets;
+mod throttle;
+
 const BLAST_URL: &str = "https://blast.ncbi.nlm.nih.gov/Blast.cgi";
 
+const EUTILS_URL: &str = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils";
+
+const VARIATION_URL: &str = "https://api.ncbi.nlm.nih.gov/variation/v0";
+
+const CD_SEARCH_URL: &str = "https://www.ncbi.nlm.nih.gov/Structure/bwrpsb/bwrpsb.cgi";
+
+const SRA_RUNINFO_URL: &str = "https://trace.ncbi.nlm.nih.gov/Traces/sra-db-be/runinfo";
+
+static API_KEY: Mutex<Option<String>> = Mutex::new(None);
+
+/// Set the NCBI API key to use for all requests, or `None` to remove it. This raises the rate limit
+/// from 3 to 10 requests per second. Get one from your NCBI account settings.
+pub fn set_api_key(key: Option<&str>) {
+    *API_KEY.lock().unwrap() = key.map(|k| k.to_owned());
+}
+
+/// The API key set with [`set_api_key`], or from the `NCBI_API_KEY` environment variable.
+pub fn api_key() -> Option<String> {
+    if let Some(key) = API_KEY.lock().unwrap().clone() {
+        return Some(key);
+    }
+
+    env::var("NCBI_API_KEY").ok().filter(|k| !k.is_empty())
+}
+
+/// An agent which applies the API key, and throttling to each request.
+pub(crate) fn make_agent_ncbi() -> Agent {
+    let config = Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(HTTP_TIMEOUT)))
+        .http_status_as_error(false)
+        .middleware(throttle::middleware)
+        .build();
+
+    config.into()
+}
+
+/// A throttled agent for large responses, without the global timeout.
+pub(crate) fn make_agent_ncbi_download() -> Agent {
+    let config = Agent::config_builder()
+        .timeout_connect(Some(Duration::from_secs(HTTP_TIMEOUT_CONNECT)))
+        .http_status_as_error(false)
+        .middleware(throttle::middleware)
+        .build();
+
+    config.into()
+}
+
+/// Entrez databases.
+/// [List](https://www.ncbi.nlm.nih.gov/books/NBK25497/table/chapter2.T._entrez_unique_identifiers_ui/)
+#[derive(Clone, PartialEq, Debug)]
+pub enum EntrezDb {
+    Pubmed,
+    Pmc,
+    Protein,
+    /// Nucleotide
+    Nuccore,
+    Gene,
+    Taxonomy,
+    Structure,
+    Snp,
+    Sra,
+    Assembly,
+    Cdd,
+    BioProject,
+    BioSample,
+    /// Any database not listed, by its Entrez name.
+    Other(String),
+}
+
+impl Display for EntrezDb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Pubmed => "pubmed",
+            Self::Pmc => "pmc",
+            Self::Protein => "protein",
+            Self::Nuccore => "nuccore",
+            Self::Gene => "gene",
+            Self::Taxonomy => "taxonomy",
+            Self::Structure => "structure",
+            Self::Snp => "snp",
+            Self::Sra => "sra",
+            Self::Assembly => "assembly",
+            Self::Cdd => "cdd",
+            Self::BioProject => "bioproject",
+            Self::BioSample => "biosample",
+            Self::Other(v) => v,
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// Build an E-utilities URL, e.g. for "esearch.fcgi".
+fn eutils_url(util: &str, params: &[(&str, &str)]) -> Url {
+    let mut url = Url::parse(&format!("{EUTILS_URL}/{util}")).unwrap();
+    {
+        let mut pairs = url.query_pairs_mut();
+        for (k, v) in params {
+            pairs.append_pair(k, v);
+        }
+    }
+    url
+}
+
+/// Make a GET request to an E-utility, returning the body.
+fn eutils_get(util: &str, params: &[(&str, &str)]) -> Result<String, ReqError> {
+    let agent = make_agent_ncbi();
+
+    let mut resp = agent.get(eutils_url(util, params).as_str()).call()?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    Ok(resp.body_mut().read_to_string()?)
+}
+
+/// Make a POST request to an E-utility, with parameters in the body, returning the body. Use this
+/// for long ID lists, which may exceed URL length limits.
+fn eutils_post(util: &str, params: &[(&str, &str)]) -> Result<String, ReqError> {
+    let agent = make_agent_ncbi();
+
+    let mut resp = agent
+        .post(format!("{EUTILS_URL}/{util}"))
+        .send_form(params.iter().copied())?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    Ok(resp.body_mut().read_to_string()?)
+}
+
+/// How Entrez interpreted a term, e.g. "aspirin" to "aspirin"[MeSH Terms] OR "aspirin"[All Fields].
+#[derive(Clone, Debug, Deserialize)]
+pub struct Translation {
+    pub from: String,
+    pub to: String,
+}
+
+/// An item of the translation stack: A term, or an operator such as "AND", "OR", or "GROUP".
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TranslationStackItem {
+    Term {
+        term: String,
+        field: String,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        count: u64,
+        explode: String,
+    },
+    Operator(String),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct EsearchResult {
+    /// The total number of matches; not just those returned.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub count: u64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub retmax: u32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub retstart: u32,
+    /// UIDs of the matches.
+    #[serde(rename = "idlist", default)]
+    pub ids: Vec<String>,
+    #[serde(rename = "translationset", default)]
+    pub translation_set: Vec<Translation>,
+    #[serde(rename = "translationstack", default)]
+    pub translation_stack: Vec<TranslationStackItem>,
+    #[serde(rename = "querytranslation")]
+    pub query_translation: Option<String>,
+    /// Set if the search was made with [`esearch_history`].
+    #[serde(rename = "webenv")]
+    pub web_env: Option<String>,
+    #[serde(rename = "querykey")]
+    pub query_key: Option<String>,
+}
+
+impl EsearchResult {
+    /// The server-side history of the results, if the search was made with [`esearch_history`].
+    pub fn history(&self) -> Option<History> {
+        Some(History {
+            web_env: self.web_env.clone()?,
+            query_key: self.query_key.clone()?,
+        })
+    }
+}
+
+/// A reference to a set of UIDs held on the Entrez history server. Use this to work with
+/// large ID sets in batches, instead of passing them in each request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct History {
+    pub web_env: String,
+    pub query_key: String,
+}
+
+impl History {
+    fn params(&self) -> [(&'static str, &str); 2] {
+        [
+            ("WebEnv", self.web_env.as_str()),
+            ("query_key", self.query_key.as_str()),
+        ]
+    }
+}
+
+/// Get the text content of the first instance of an XML tag. For simple responses only.
+fn xml_tag<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+
+    let start = text.find(&open)? + open.len();
+    let end = start + text[start..].find(&close)?;
+
+    Some(text[start..end].trim())
+}
+
+/// Get the contents of each instance of an XML tag, non-nested. For simple responses only.
+fn xml_blocks<'a>(text: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+
+    let mut result = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(&close) else {
+            break;
+        };
+        result.push(&after[..end]);
+        rest = &after[end + close.len()..];
+    }
+
+    result
+}
+
+/// A searchable field of an Entrez database, e.g. "[TITL]".
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DbField {
+    /// The abbreviation used in search terms, e.g. "TITL".
+    pub name: String,
+    /// E.g. "Title".
+    #[serde(rename = "fullname")]
+    pub full_name: String,
+    pub description: String,
+    #[serde(
+        rename = "termcount",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub term_count: u64,
+    /// "Y" or "N".
+    #[serde(rename = "isdate")]
+    pub is_date: String,
+    /// "Y" or "N".
+    #[serde(rename = "isnumerical")]
+    pub is_numerical: String,
+    /// "Y" or "N".
+    #[serde(rename = "ishidden")]
+    pub is_hidden: String,
+}
+
+/// A link from an Entrez database to another, for use with [`elink`].
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DbLink {
+    /// E.g. "pubmed_protein".
+    pub name: String,
+    /// E.g. "Protein Links".
+    pub menu: String,
+    pub description: String,
+    #[serde(rename = "dbto")]
+    pub db_to: String,
+}
+
+/// Information about an Entrez database.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DbInfo {
+    #[serde(rename = "dbname")]
+    pub db_name: String,
+    /// E.g. "PubMed".
+    #[serde(rename = "menuname")]
+    pub menu_name: String,
+    pub description: String,
+    /// The number of records.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub count: u64,
+    #[serde(rename = "lastupdate")]
+    pub last_update: String,
+    #[serde(rename = "fieldlist")]
+    pub fields: Vec<DbField>,
+    #[serde(rename = "linklist")]
+    pub links: Vec<DbLink>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct EinfoResult {
+    #[serde(rename = "dblist")]
+    db_list: Option<Vec<String>>,
+    #[serde(rename = "dbinfo")]
+    db_info: Option<OneOrMany<DbInfo>>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct EinfoResp {
+    einforesult: EinfoResult,
+}
+
+/// List the names of all Entrez databases, e.g. "pubmed", "protein".
+pub fn einfo_databases() -> Result<Vec<String>, ReqError> {
+    let resp = eutils_get("einfo.fcgi", &[("retmode", "json")])?;
+
+    let parsed: EinfoResp = serde_json::from_str(&resp)?;
+    parsed.einforesult.db_list.ok_or(ReqError::Deserialize)
+}
+
+/// Load information about an Entrez database: Its record count, searchable fields, and the links
+/// available from it.
+pub fn einfo(db: EntrezDb) -> Result<DbInfo, ReqError> {
+    let db_name = db.to_string();
+
+    let resp = eutils_get(
+        "einfo.fcgi",
+        &[("db", &db_name), ("version", "2.0"), ("retmode", "json")],
+    )?;
+
+    let parsed: EinfoResp = serde_json::from_str(&resp)?;
+    let info = match parsed.einforesult.db_info {
+        Some(OneOrMany::One(v)) => Some(v),
+        Some(OneOrMany::Many(v)) => v.into_iter().next(),
+        None => None,
+    };
+
+    info.ok_or(ReqError::NotFound { ident: db_name })
+}
+
+/// The number of matches for a term in one database.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DbCount {
+    /// E.g. "pubmed".
+    pub db_name: String,
+    /// E.g. "PubMed".
+    pub menu_name: String,
+    pub count: u64,
+}
+
+/// Count the matches for a term in each Entrez database. Use this to find which databases are
+/// worth searching, e.g. with [`esearch`]. Databases with errors are skipped.
+pub fn egquery(term: &str) -> Result<Vec<DbCount>, ReqError> {
+    // egquery only returns XML.
+    let resp = eutils_get("egquery.fcgi", &[("term", term)])?;
+
+    Ok(xml_blocks(&resp, "ResultItem")
+        .into_iter()
+        .filter(|item| xml_tag(item, "Status").is_none_or(|s| s == "Ok"))
+        .filter_map(|item| {
+            Some(DbCount {
+                db_name: xml_tag(item, "DbName")?.to_owned(),
+                menu_name: xml_tag(item, "MenuName").unwrap_or_default().to_owned(),
+                count: xml_tag(item, "Count")?.parse().ok()?,
+            })
+        })
+        .collect())
+}
+
+/// Upload a list of UIDs to the history server, for use with the `_history` functions.
+pub fn epost(db: EntrezDb, ids: &[&str]) -> Result<History, ReqError> {
+    let db = db.to_string();
+    let ids = ids.join(",");
+
+    // epost only returns XML.
+    let resp = eutils_post("epost.fcgi", &[("db", &db), ("id", &ids)])?;
+
+    Ok(History {
+        web_env: xml_tag(&resp, "WebEnv")
+            .ok_or(ReqError::Deserialize)?
+            .to_owned(),
+        query_key: xml_tag(&resp, "QueryKey")
+            .ok_or(ReqError::Deserialize)?
+            .to_owned(),
+    })
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct EsearchResp {
+    esearchresult: EsearchResult,
+}
+
+/// Search an Entrez database by term, e.g. `esearch(EntrezDb::Protein, "EGFR[gene] AND human[orgn]", 20, 0)`.
+/// Returns up to `retmax` UIDs, starting at index `retstart`.
+pub fn esearch(
+    db: EntrezDb,
+    term: &str,
+    retmax: u32,
+    retstart: u32,
+) -> Result<EsearchResult, ReqError> {
+    let db = db.to_string();
+    let retmax = retmax.to_string();
+    let retstart = retstart.to_string();
+
+    let resp = eutils_get(
+        "esearch.fcgi",
+        &[
+            ("db", &db),
+            ("term", term),
+            ("retmax", &retmax),
+            ("retstart", &retstart),
+            ("retmode", "json"),
+        ],
+    )?;
+
+    let parsed: EsearchResp = serde_json::from_str(&resp)?;
+    Ok(parsed.esearchresult)
+}
+
+/// Search an Entrez database by term, storing all results on the history server. Retrieve them in
+/// batches with the `_history` functions, using [`EsearchResult::history`], and `count`.
+pub fn esearch_history(db: EntrezDb, term: &str) -> Result<EsearchResult, ReqError> {
+    let db = db.to_string();
+
+    let resp = eutils_get(
+        "esearch.fcgi",
+        &[
+            ("db", &db),
+            ("term", term),
+            ("usehistory", "y"),
+            ("retmax", "0"),
+            ("retmode", "json"),
+        ],
+    )?;
+
+    let parsed: EsearchResp = serde_json::from_str(&resp)?;
+    Ok(parsed.esearchresult)
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DocSumOrganism {
+    #[serde(rename = "scientificname")]
+    pub scientific_name: String,
+    #[serde(rename = "commonname")]
+    pub common_name: String,
+    #[serde(rename = "taxid")]
+    pub tax_id: u32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GenomicInfo {
+    /// Chromosome
+    #[serde(rename = "chrloc")]
+    pub chr_loc: String,
+    /// RefSeq accession of the chromosome, e.g. "NC_000007.14".
+    #[serde(rename = "chraccver")]
+    pub chr_acc_ver: String,
+    /// 0-based
+    #[serde(rename = "chrstart")]
+    pub chr_start: u64,
+    /// 0-based
+    #[serde(rename = "chrstop")]
+    pub chr_stop: u64,
+    #[serde(rename = "exoncount")]
+    pub exon_count: u32,
+}
+
+/// Document summary from the gene database.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GeneDocSum {
+    pub uid: String,
+    /// The official symbol, e.g. "EGFR".
+    pub name: String,
+    pub description: String,
+    pub chromosome: String,
+    #[serde(rename = "maplocation")]
+    pub map_location: String,
+    /// Comma-separated.
+    #[serde(rename = "otheraliases")]
+    pub other_aliases: String,
+    pub summary: String,
+    pub organism: DocSumOrganism,
+    #[serde(rename = "genomicinfo")]
+    pub genomic_info: Vec<GenomicInfo>,
+}
+
+/// Document summary from the protein, or nucleotide (nuccore) databases.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SequenceDocSum {
+    pub uid: String,
+    /// The accession, without version.
+    pub caption: String,
+    pub title: String,
+    #[serde(rename = "accessionversion")]
+    pub accession_version: String,
+    /// Sequence length.
+    pub slen: u64,
+    /// E.g. "aa", "dna", "rna".
+    #[serde(rename = "moltype")]
+    pub mol_type: String,
+    #[serde(rename = "taxid")]
+    pub tax_id: u32,
+    pub organism: String,
+    #[serde(rename = "createdate")]
+    pub create_date: String,
+    #[serde(rename = "updatedate")]
+    pub update_date: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DocSumAuthor {
+    pub name: String,
+    #[serde(rename = "authtype")]
+    pub auth_type: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ArticleId {
+    /// E.g. "pubmed", "doi", "pmc".
+    #[serde(rename = "idtype")]
+    pub id_type: String,
+    pub value: String,
+}
+
+/// Document summary from the PubMed database.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PubmedDocSum {
+    pub uid: String,
+    pub title: String,
+    #[serde(rename = "pubdate")]
+    pub pub_date: String,
+    /// The journal's abbreviated name.
+    pub source: String,
+    #[serde(rename = "fulljournalname")]
+    pub full_journal_name: String,
+    pub authors: Vec<DocSumAuthor>,
+    pub volume: String,
+    pub issue: String,
+    pub pages: String,
+    #[serde(rename = "articleids")]
+    pub article_ids: Vec<ArticleId>,
+}
+
+impl PubmedDocSum {
+    pub fn doi(&self) -> Option<&str> {
+        self.article_ids
+            .iter()
+            .find(|a| a.id_type == "doi")
+            .map(|a| a.value.as_str())
+    }
+}
+
+/// Document summary from the structure (MMDB) database.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct StructureDocSum {
+    pub uid: String,
+    /// The PDB ID.
+    #[serde(rename = "pdbacc")]
+    pub pdb_acc: String,
+    #[serde(rename = "pdbdescr")]
+    pub pdb_descr: String,
+    /// E.g. "X-Ray Diffraction".
+    #[serde(rename = "expmethod")]
+    pub exp_method: String,
+    /// Å, if applicable.
+    pub resolution: String,
+    #[serde(rename = "pdbclass")]
+    pub pdb_class: String,
+    #[serde(rename = "pdbdepositdate")]
+    pub pdb_deposit_date: String,
+    #[serde(rename = "organismlist")]
+    pub organism_list: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssemblySynonym {
+    /// The GenBank (GCA) accession.
+    pub genbank: String,
+    /// The RefSeq (GCF) accession.
+    pub refseq: String,
+}
+
+/// Document summary from the assembly database.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssemblyDocSum {
+    pub uid: String,
+    /// E.g. "GCF_000001405.40".
+    #[serde(rename = "assemblyaccession")]
+    pub assembly_accession: String,
+    /// E.g. "GRCh38.p14".
+    #[serde(rename = "assemblyname")]
+    pub assembly_name: String,
+    /// E.g. "hg38".
+    #[serde(rename = "ucscname")]
+    pub ucsc_name: String,
+    #[serde(rename = "taxid", deserialize_with = "deserialize_number_from_string")]
+    pub tax_id: u32,
+    /// E.g. "Homo sapiens (human)".
+    pub organism: String,
+    #[serde(rename = "speciesname")]
+    pub species_name: String,
+    /// The assembly level: "Complete Genome", "Chromosome", "Scaffold", or "Contig".
+    #[serde(rename = "assemblystatus")]
+    pub assembly_status: String,
+    /// E.g. "haploid".
+    #[serde(rename = "assemblytype")]
+    pub assembly_type: String,
+    /// E.g. "reference genome".
+    pub refseq_category: String,
+    #[serde(rename = "submitterorganization")]
+    pub submitter_organization: String,
+    #[serde(rename = "asmreleasedate_genbank")]
+    pub release_date_genbank: String,
+    #[serde(rename = "biosampleaccn")]
+    pub biosample_accession: String,
+    pub synonym: AssemblySynonym,
+    /// The FTP directory of the RefSeq assembly's sequence and annotation files.
+    #[serde(rename = "ftppath_refseq")]
+    pub ftp_path_refseq: String,
+    /// The FTP directory of the GenBank assembly's sequence and annotation files.
+    #[serde(rename = "ftppath_genbank")]
+    pub ftp_path_genbank: String,
+    #[serde(rename = "ftppath_assembly_rpt")]
+    pub ftp_path_assembly_report: String,
+    #[serde(rename = "ftppath_stats_rpt")]
+    pub ftp_path_stats_report: String,
+    #[serde(rename = "contign50")]
+    pub contig_n50: u64,
+    #[serde(rename = "scaffoldn50")]
+    pub scaffold_n50: u64,
+    /// E.g. "30.0".
+    pub coverage: String,
+    /// XML, containing stats. Parsed into [`Assembly`] by [`get_assembly`].
+    pub meta: String,
+}
+
+/// Load document summaries. `T` is the summary type for the database, e.g. [`GeneDocSum`] for
+/// `EntrezDb::Gene`, or [`SequenceDocSum`] for `EntrezDb::Protein`. Use `serde_json::Value` for
+/// other databases. Summaries are returned in the order of `ids`; ones that don't exist are skipped.
+pub fn esummary<T: DeserializeOwned>(db: EntrezDb, ids: &[&str]) -> Result<Vec<T>, ReqError> {
+    let db = db.to_string();
+    let ids = ids.join(",");
+
+    let resp = eutils_post(
+        "esummary.fcgi",
+        &[("db", &db), ("id", &ids), ("retmode", "json")],
+    )?;
+
+    parse_esummary(&resp)
+}
+
+/// Like [`esummary`], but for a batch of UIDs on the history server, starting at index `retstart`.
+pub fn esummary_history<T: DeserializeOwned>(
+    db: EntrezDb,
+    history: &History,
+    retstart: u32,
+    retmax: u32,
+) -> Result<Vec<T>, ReqError> {
+    let db = db.to_string();
+    let retstart = retstart.to_string();
+    let retmax = retmax.to_string();
+
+    let mut params = vec![
+        ("db", db.as_str()),
+        ("retstart", &retstart),
+        ("retmax", &retmax),
+        ("retmode", "json"),
+    ];
+    params.extend(history.params());
+
+    let resp = eutils_post("esummary.fcgi", &params)?;
+    parse_esummary(&resp)
+}
+
+/// Parse an esummary JSON response. The result is keyed by UID, with the order in "uids".
+fn parse_esummary<T: DeserializeOwned>(resp: &str) -> Result<Vec<T>, ReqError> {
+    let mut parsed: serde_json::Value = serde_json::from_str(resp)?;
+    let Some(result) = parsed.get_mut("result").and_then(|r| r.as_object_mut()) else {
+        return Err(ReqError::Deserialize);
+    };
+
+    let uids: Vec<String> = match result.get("uids") {
+        Some(u) => serde_json::from_value(u.clone())?,
+        None => Vec::new(),
+    };
+
+    let mut docsums = Vec::with_capacity(uids.len());
+    for uid in uids {
+        let Some(v) = result.remove(&uid) else {
+            continue;
+        };
+        // E.g. `{"uid": "1", "error": "cannot get document summary"}`.
+        if v.get("error").is_some() {
+            continue;
+        }
+        docsums.push(serde_json::from_value(v)?);
+    }
+
+    Ok(docsums)
+}
+
+/// A citation to match to a PubMed ID with [`ecitmatch`]. Fields not known may be left blank, but
+/// matches are more reliable with more of them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CitationQuery {
+    /// The journal's abbreviated title, e.g. "Proc Natl Acad Sci U S A".
+    pub journal: String,
+    pub year: Option<u16>,
+    pub volume: Option<String>,
+    pub first_page: Option<String>,
+    /// The first author, as surname and initials, e.g. "Mann BJ".
+    pub author: Option<String>,
+}
+
+impl From<&Citation> for CitationQuery {
+    fn from(c: &Citation) -> Self {
+        // RCSB authors are in the form "Mann, B.J.".
+        let author =
+            c.rcsb_authors
+                .as_ref()
+                .and_then(|a| a.first())
+                .map(|a| match a.split_once(',') {
+                    Some((surname, initials)) => {
+                        let initials: String =
+                            initials.chars().filter(|c| c.is_alphabetic()).collect();
+                        format!("{} {initials}", surname.trim())
+                    }
+                    None => a.trim().to_owned(),
+                });
+
+        Self {
+            journal: c.rcsb_journal_abbrev.replace('.', " "),
+            year: c.year,
+            volume: c.journal_volume.map(|v| v.to_string()),
+            first_page: c.page_first.clone(),
+            author,
+        }
+    }
+}
+
+/// Find PubMed IDs for citations, in a batch. Returns a result for each citation, in order; `None`
+/// if there's no unique match. Citations from the `rcsb` module can be converted with `into()`;
+/// e.g. to find PubMed records when `pdbx_database_id_pub_med` is missing.
+pub fn ecitmatch(citations: &[CitationQuery]) -> Result<Vec<Option<u32>>, ReqError> {
+    if citations.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Pipes delimit fields, so they can't appear in values.
+    let clean = |v: &str| v.replace('|', " ");
+
+    // Each citation is "journal|year|volume|first_page|author|key|", separated by carriage returns.
+    // We use the index as the key.
+    let bdata = citations
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            format!(
+                "{}|{}|{}|{}|{}|{i}|",
+                clean(&c.journal),
+                c.year.map(|y| y.to_string()).unwrap_or_default(),
+                clean(c.volume.as_deref().unwrap_or_default()),
+                clean(c.first_page.as_deref().unwrap_or_default()),
+                clean(c.author.as_deref().unwrap_or_default()),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\r");
+
+    let resp = eutils_post(
+        "ecitmatch.cgi",
+        &[("db", "pubmed"), ("retmode", "xml"), ("bdata", &bdata)],
+    )?;
+
+    // Each response line is the input, followed by the PMID, or e.g. "NOT_FOUND", or "AMBIGUOUS".
+    let mut result = vec![None; citations.len()];
+    for line in resp.lines() {
+        let fields: Vec<&str> = line.split('|').map(|f| f.trim()).collect();
+        if fields.len() < 7 {
+            continue;
+        }
+        let Ok(i) = fields[5].parse::<usize>() else {
+            continue;
+        };
+        if let Some(r) = result.get_mut(i) {
+            *r = fields[6].parse().ok();
+        }
+    }
+
+    Ok(result)
+}
+
+/// Links from a set of source records to one database.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LinkSetDb {
+    #[serde(rename = "dbto")]
+    pub db_to: String,
+    /// E.g. "protein_gene".
+    #[serde(rename = "linkname")]
+    pub link_name: String,
+    /// UIDs in the target database.
+    pub links: Vec<String>,
+}
+
+/// Links from one source record.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LinkSet {
+    #[serde(rename = "dbfrom")]
+    pub db_from: String,
+    /// The source UIDs.
+    pub ids: Vec<String>,
+    #[serde(rename = "linksetdbs")]
+    pub link_set_dbs: Vec<LinkSetDb>,
+}
+
+impl LinkSet {
+    /// All linked UIDs, across link names.
+    pub fn links(&self) -> Vec<&str> {
+        self.link_set_dbs
+            .iter()
+            .flat_map(|l| l.links.iter().map(|v| v.as_str()))
+            .collect()
+    }
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct ElinkResp {
+    #[serde(rename = "linksets", default)]
+    link_sets: Vec<LinkSet>,
+}
+
+/// Find records in one database linked to records in another, e.g. protein to gene, gene to
+/// pubmed, or nuccore to taxonomy. Returns a link set per source UID.
+pub fn elink(from_db: EntrezDb, to_db: EntrezDb, ids: &[&str]) -> Result<Vec<LinkSet>, ReqError> {
+    let from_db = from_db.to_string();
+    let to_db = to_db.to_string();
+
+    // Separate `id` parameters, vice comma-separated, give us a link set per ID.
+    let mut params = vec![
+        ("dbfrom", from_db.as_str()),
+        ("db", to_db.as_str()),
+        ("retmode", "json"),
+    ];
+    for id in ids {
+        params.push(("id", id));
+    }
+
+    let resp = eutils_post("elink.fcgi", &params)?;
+
+    let parsed: ElinkResp = serde_json::from_str(&resp)?;
+    Ok(parsed.link_sets)
+}
+
+/// An ancestor of a taxon.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineageTaxon {
+    pub tax_id: u32,
+    pub scientific_name: String,
+    /// E.g. "genus", "family", or "no rank".
+    pub rank: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NcbiTaxon {
+    pub tax_id: u32,
+    pub scientific_name: String,
+    pub common_name: Option<String>,
+    /// E.g. "species".
+    pub rank: String,
+    /// E.g. "Primates".
+    pub division: Option<String>,
+    /// From the root, e.g. "cellular organisms", to the parent.
+    pub lineage: Vec<LineageTaxon>,
+}
+
+/// Look up a taxon by NCBI Taxonomy ID (e.g. "9606"), or scientific name (e.g. "Homo sapiens"). This
+/// can be used to normalize organism names, e.g. from RCSB or UniProt records, to tax IDs.
+pub fn get_taxonomy(taxid_or_name: &str) -> Result<NcbiTaxon, ReqError> {
+    let ident = taxid_or_name.trim();
+
+    let tax_id = if ident.chars().all(|c| c.is_ascii_digit()) {
+        ident.to_owned()
+    } else {
+        let term = format!("\"{ident}\"[Scientific Name]");
+        esearch(EntrezDb::Taxonomy, &term, 1, 0)?
+            .ids
+            .into_iter()
+            .next()
+            .ok_or(ReqError::NotFound {
+                ident: ident.to_owned(),
+            })?
+    };
+
+    // Taxonomy efetch only returns XML.
+    let resp = eutils_get("efetch.fcgi", &[("db", "taxonomy"), ("id", &tax_id)])?;
+
+    let not_found = || ReqError::NotFound {
+        ident: ident.to_owned(),
+    };
+    let start = resp.find("<Taxon>").ok_or_else(not_found)?;
+    let taxon = &resp[start..];
+
+    // The lineage is nested in the taxon, after its own fields. Separate it, so its fields don't
+    // match the taxon's.
+    let own = match taxon.find("<LineageEx>") {
+        Some(i) => &taxon[..i],
+        None => taxon,
+    };
+    let lineage_ex = xml_tag(taxon, "LineageEx").unwrap_or_default();
+
+    let lineage = xml_blocks(lineage_ex, "Taxon")
+        .into_iter()
+        .filter_map(|t| {
+            Some(LineageTaxon {
+                tax_id: xml_tag(t, "TaxId")?.parse().ok()?,
+                scientific_name: xml_tag(t, "ScientificName")?.to_owned(),
+                rank: xml_tag(t, "Rank").unwrap_or_default().to_owned(),
+            })
+        })
+        .collect();
+
+    Ok(NcbiTaxon {
+        tax_id: xml_tag(own, "TaxId")
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(not_found)?,
+        scientific_name: xml_tag(own, "ScientificName")
+            .ok_or(ReqError::Deserialize)?
+            .to_owned(),
+        common_name: xml_tag(own, "GenbankCommonName")
+            .or_else(|| xml_tag(own, "CommonName"))
+            .map(|v| v.to_owned()),
+        rank: xml_tag(own, "Rank").unwrap_or_default().to_owned(),
+        division: xml_tag(own, "Division").map(|v| v.to_owned()),
+        lineage,
+    })
+}
+
+/// A gene, with its RefSeq transcript and protein accessions.
+#[derive(Clone, Debug)]
+pub struct NcbiGene {
+    /// Includes the official symbol, description, and genomic context.
+    pub summary: GeneDocSum,
+    /// E.g. "NM_005228.5".
+    pub refseq_rna: Vec<String>,
+    /// E.g. "NP_005219.2".
+    pub refseq_protein: Vec<String>,
+}
+
+/// Accessions (with version) of the records linked from one record, by link name.
+fn linked_accessions(
+    from_db: EntrezDb,
+    to_db: EntrezDb,
+    id: &str,
+    link_name: &str,
+) -> Result<Vec<String>, ReqError> {
+    let link_sets = elink(from_db, to_db.clone(), &[id])?;
+
+    let uids: Vec<&str> = link_sets
+        .iter()
+        .flat_map(|l| &l.link_set_dbs)
+        .filter(|l| l.link_name == link_name)
+        .flat_map(|l| l.links.iter().map(|v| v.as_str()))
+        .collect();
+
+    if uids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let docsums: Vec<SequenceDocSum> = esummary(to_db, &uids)?;
+    Ok(docsums.into_iter().map(|d| d.accession_version).collect())
+}
+
+/// Look up a gene by NCBI Gene ID (e.g. "1956"), or symbol (e.g. "EGFR"). `organism` narrows symbol
+/// searches, e.g. "human", or "Mus musculus"; otherwise, the top match is returned.
+pub fn get_gene(gene_id_or_symbol: &str, organism: Option<&str>) -> Result<NcbiGene, ReqError> {
+    let ident = gene_id_or_symbol.trim();
+    let not_found = || ReqError::NotFound {
+        ident: ident.to_owned(),
+    };
+
+    let gene_id = if ident.chars().all(|c| c.is_ascii_digit()) {
+        ident.to_owned()
+    } else {
+        let term = match organism {
+            Some(org) => format!("{ident}[sym] AND {org}[orgn]"),
+            None => format!("{ident}[sym]"),
+        };
+        esearch(EntrezDb::Gene, &term, 1, 0)?
+            .ids
+            .into_iter()
+            .next()
+            .ok_or_else(not_found)?
+    };
+
+    let summary: GeneDocSum = esummary(EntrezDb::Gene, &[&gene_id])?
+        .into_iter()
+        .next()
+        .ok_or_else(not_found)?;
+
+    let refseq_rna = linked_accessions(
+        EntrezDb::Gene,
+        EntrezDb::Nuccore,
+        &gene_id,
+        "gene_nuccore_refseqrna",
+    )?;
+    let refseq_protein = linked_accessions(
+        EntrezDb::Gene,
+        EntrezDb::Protein,
+        &gene_id,
+        "gene_protein_refseq",
+    )?;
+
+    Ok(NcbiGene {
+        summary,
+        refseq_rna,
+        refseq_protein,
+    })
+}
+
+/// Genome assembly statistics, over all sequences.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssemblyDbStats {
+    /// In base pairs.
+    pub total_length: Option<u64>,
+    pub ungapped_length: Option<u64>,
+    pub chromosome_count: Option<u32>,
+    pub scaffold_count: Option<u32>,
+    pub contig_count: Option<u32>,
+    pub replicon_count: Option<u32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Assembly {
+    /// Includes the accessions, assembly level, and FTP paths.
+    pub summary: AssemblyDocSum,
+    pub stats: AssemblyDbStats,
+}
+
+impl Assembly {
+    /// The FTP directory of the assembly's files; RefSeq if available, otherwise GenBank.
+    pub fn ftp_path(&self) -> Option<&str> {
+        [
+            &self.summary.ftp_path_refseq,
+            &self.summary.ftp_path_genbank,
+        ]
+        .into_iter()
+        .find(|p| !p.is_empty())
+        .map(|p| p.as_str())
+    }
+}
+
+/// Parse the assembly-wide stats from an assembly docsum's `meta` XML. These are of the form
+/// `<Stat category="total_length" sequence_tag="all">3099441038</Stat>`.
+fn parse_assembly_stats(meta: &str) -> AssemblyDbStats {
+    let mut result = AssemblyDbStats::default();
+
+    let mut rest = meta;
+    while let Some(start) = rest.find("<Stat ") {
+        let after = &rest[start..];
+        let Some(close) = after.find('>') else {
+            break;
+        };
+        let Some(end) = after.find("</Stat>") else {
+            break;
+        };
+        let attrs = &after[..close];
+        let value = after[close + 1..end].trim();
+        rest = &after[end..];
+
+        if !attrs.contains("sequence_tag=\"all\"") {
+            continue;
+        }
+        let Some(category) = attrs
+            .split("category=\"")
+            .nth(1)
+            .and_then(|v| v.split('"').next())
+        else {
+            continue;
+        };
+
+        match category {
+            "total_length" => result.total_length = value.parse().ok(),
+            "ungapped_length" => result.ungapped_length = value.parse().ok(),
+            "chromosome_count" => result.chromosome_count = value.parse().ok(),
+            "scaffold_count" => result.scaffold_count = value.parse().ok(),
+            "contig_count" => result.contig_count = value.parse().ok(),
+            "replicon_count" => result.replicon_count = value.parse().ok(),
+            _ => (),
+        }
+    }
+
+    result
+}
+
+/// Look up a genome assembly by accession (e.g. "GCF_000001405.40"), assembly name (e.g.
+/// "GRCh38.p14"), or organism name (e.g. "Escherichia coli"). For organisms, this returns the latest
+/// RefSeq assembly, preferring reference genomes. Use [`Assembly::ftp_path`] to locate its sequence
+/// files. See also the `datasets` module.
+pub fn get_assembly(accession_or_name: &str) -> Result<Assembly, ReqError> {
+    let ident = accession_or_name.trim();
+    let not_found = || ReqError::NotFound {
+        ident: ident.to_owned(),
+    };
+
+    let terms = if ident.starts_with("GCF_") || ident.starts_with("GCA_") {
+        vec![format!("{ident}[Assembly Accession]")]
+    } else {
+        vec![
+            format!("\"{ident}\"[Assembly Name]"),
+            format!("\"{ident}\"[Organism] AND \"reference genome\"[filter]"),
+            format!("\"{ident}\"[Organism] AND latest_refseq[filter]"),
+        ]
+    };
+
+    let mut uid = None;
+    for term in terms {
+        if let Some(id) = esearch(EntrezDb::Assembly, &term, 1, 0)?
+            .ids
+            .into_iter()
+            .next()
+        {
+            uid = Some(id);
+            break;
+        }
+    }
+    let uid = uid.ok_or_else(not_found)?;
+
+    let summary: AssemblyDocSum = esummary(EntrezDb::Assembly, &[&uid])?
+        .into_iter()
+        .next()
+        .ok_or_else(not_found)?;
+    let stats = parse_assembly_stats(&summary.meta);
+
+    Ok(Assembly { summary, stats })
+}
+
+/// A record from a FASTA file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FastaRecord {
+    /// The definition line, without the leading '>'. E.g. "NP_005219.2 epidermal growth factor
+    /// receptor isoform a precursor [Homo sapiens]".
+    pub header: String,
+    /// Line breaks are removed.
+    pub seq: String,
+}
+
+impl FastaRecord {
+    /// The accession: The first word of the header.
+    pub fn accession(&self) -> &str {
+        self.header.split_whitespace().next().unwrap_or_default()
+    }
+}
+
+/// Parse FASTA text into header/sequence pairs.
+pub fn parse_fasta(text: &str) -> Vec<FastaRecord> {
+    let mut result: Vec<FastaRecord> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('>') {
+            result.push(FastaRecord {
+                header: header.to_owned(),
+                seq: String::new(),
+            });
+        } else if let Some(rec) = result.last_mut() {
+            rec.seq.push_str(line);
+        }
+    }
+
+    result
+}
+
+/// Fetch records as FASTA text from a sequence database, e.g. `EntrezDb::Protein`, or
+/// `EntrezDb::Nuccore`. `ids` are UIDs or accessions. Parse with [`parse_fasta`] A/R.
+pub fn efetch_fasta(db: EntrezDb, ids: &[&str]) -> Result<String, ReqError> {
+    let db = db.to_string();
+    let ids = ids.join(",");
+
+    eutils_post(
+        "efetch.fcgi",
+        &[
+            ("db", &db),
+            ("id", &ids),
+            ("rettype", "fasta"),
+            ("retmode", "text"),
+        ],
+    )
+}
+
+/// Like [`efetch_fasta`], but for a batch of UIDs on the history server, starting at index `retstart`.
+pub fn efetch_fasta_history(
+    db: EntrezDb,
+    history: &History,
+    retstart: u32,
+    retmax: u32,
+) -> Result<String, ReqError> {
+    let db = db.to_string();
+    let retstart = retstart.to_string();
+    let retmax = retmax.to_string();
+
+    let mut params = vec![
+        ("db", db.as_str()),
+        ("retstart", &retstart),
+        ("retmax", &retmax),
+        ("rettype", "fasta"),
+        ("retmode", "text"),
+    ];
+    params.extend(history.params());
+
+    eutils_post("efetch.fcgi", &params)
+}
+
+/// Fetch nucleotide records as GenBank flat files, by UID or accession.
+pub fn efetch_genbank(ids: &[&str]) -> Result<String, ReqError> {
+    let ids = ids.join(",");
+
+    eutils_post(
+        "efetch.fcgi",
+        &[
+            ("db", "nuccore"),
+            ("id", &ids),
+            ("rettype", "gbwithparts"),
+            ("retmode", "text"),
+        ],
+    )
+}
+
+/// Like [`efetch_genbank`], but for a batch of UIDs on the history server, starting at index `retstart`.
+pub fn efetch_genbank_history(
+    history: &History,
+    retstart: u32,
+    retmax: u32,
+) -> Result<String, ReqError> {
+    let retstart = retstart.to_string();
+    let retmax = retmax.to_string();
+
+    let mut params = vec![
+        ("db", "nuccore"),
+        ("retstart", &retstart),
+        ("retmax", &retmax),
+        ("rettype", "gbwithparts"),
+        ("retmode", "text"),
+    ];
+    params.extend(history.params());
+
+    eutils_post("efetch.fcgi", &params)
+}
+
+/// Split GenBank flat file text into records, each ending with "//".
+pub fn split_genbank(text: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        if line.trim().is_empty() && current.is_empty() {
+            continue;
+        }
+        current.push_str(line);
+        current.push('\n');
+
+        if line.trim_end() == "//" {
+            result.push(std::mem::take(&mut current));
+        }
+    }
+
+    result
+}
+
+/// The UIDs to fetch with [`EfetchBatches`].
+#[derive(Clone, Debug)]
+pub enum EfetchSource {
+    /// UIDs or accessions.
+    Ids(Vec<String>),
+    /// UIDs on the history server, e.g. from [`esearch_history`], or [`epost`]. `count` is the number
+    /// of UIDs; e.g. [`EsearchResult::count`].
+    History { history: History, count: u64 },
+}
+
+impl EfetchSource {
+    fn len(&self) -> u64 {
+        match self {
+            Self::Ids(ids) => ids.len() as u64,
+            Self::History { count, .. } => *count,
+        }
+    }
+}
+
+/// An iterator that fetches records in chunks, with retries, and yields them parsed. Use this for
+/// large ID sets, e.g. thousands of sequences from a search.
+///
+/// If a chunk fails after all retries, its error is yielded once, and iteration continues with the
+/// next chunk.
+///
+/// Example:
+/// ```ignore
+/// let search = esearch_history(EntrezDb::Protein, "EGFR[gene]")?;
+/// let source = EfetchSource::History { history: search.history().unwrap(), count: search.count };
+///
+/// for rec in EfetchBatches::fasta(EntrezDb::Protein, source) {
+///     let rec = rec?;
+/// }
+/// ```
+pub struct EfetchBatches<T> {
+    db: EntrezDb,
+    source: EfetchSource,
+    rettype: &'static str,
+    parse: fn(&str) -> Vec<T>,
+    /// The number of records to request at once. NCBI recommends no more than 500 for sequences.
+    pub chunk_size: u32,
+    /// The number of times to retry a chunk after a failure.
+    pub max_retries: u8,
+    /// The index of the first record of the next chunk.
+    retstart: u64,
+    buffer: std::vec::IntoIter<T>,
+}
+
+impl EfetchBatches<FastaRecord> {
+    /// Fetch records as FASTA, from a sequence database, e.g. `EntrezDb::Protein`.
+    pub fn fasta(db: EntrezDb, source: EfetchSource) -> Self {
+        Self::new(db, source, "fasta", parse_fasta)
+    }
+}
+
+impl EfetchBatches<String> {
+    /// Fetch nucleotide records as GenBank flat files, one string per record.
+    pub fn genbank(source: EfetchSource) -> Self {
+        Self::new(EntrezDb::Nuccore, source, "gbwithparts", split_genbank)
+    }
+}
+
+impl<T> EfetchBatches<T> {
+    fn new(
+        db: EntrezDb,
+        source: EfetchSource,
+        rettype: &'static str,
+        parse: fn(&str) -> Vec<T>,
+    ) -> Self {
+        Self {
+            db,
+            source,
+            rettype,
+            parse,
+            chunk_size: 200,
+            max_retries: 3,
+            retstart: 0,
+            buffer: Vec::new().into_iter(),
+        }
+    }
+
+    /// The total number of records to fetch.
+    pub fn len(&self) -> u64 {
+        self.source.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The chunk size, treating 0 as 1, so we always make progress.
+    fn chunk_len(&self) -> u32 {
+        self.chunk_size.max(1)
+    }
+
+    fn fetch_chunk(&self) -> Result<String, ReqError> {
+        let db = self.db.to_string();
+        let retstart = self.retstart.to_string();
+        let retmax = self.chunk_len().to_string();
+
+        let mut params = vec![
+            ("db", db.as_str()),
+            ("rettype", self.rettype),
+            ("retmode", "text"),
+        ];
+
+        let ids;
+        match &self.source {
+            EfetchSource::Ids(all) => {
+                let end = (self.retstart as usize + self.chunk_len() as usize).min(all.len());
+                ids = all[self.retstart as usize..end].join(",");
+                params.push(("id", &ids));
+            }
+            EfetchSource::History { history, .. } => {
+                params.push(("retstart", &retstart));
+                params.push(("retmax", &retmax));
+                params.extend(history.params());
+            }
+        }
+
+        let resp = eutils_post("efetch.fcgi", &params)?;
+
+        // Errors are sometimes reported in the body of a successful response.
+        if resp.trim_start().starts_with("<?xml") || resp.contains("<ERROR>") {
+            return Err(ReqError::Http);
+        }
+
+        Ok(resp)
+    }
+}
+
+impl<T> Iterator for EfetchBatches<T> {
+    type Item = Result<T, ReqError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(rec) = self.buffer.next() {
+            return Some(Ok(rec));
+        }
+
+        while self.retstart < self.len() {
+            let mut attempt = 0;
+            let resp = loop {
+                match self.fetch_chunk() {
+                    Ok(r) => break Ok(r),
+                    Err(e) if attempt >= self.max_retries => break Err(e),
+                    Err(_) => {
+                        attempt += 1;
+                        thread::sleep(Duration::from_secs(attempt as u64));
+                    }
+                }
+            };
+
+            self.retstart += self.chunk_len() as u64;
+
+            match resp {
+                Ok(r) => {
+                    self.buffer = (self.parse)(&r).into_iter();
+                    if let Some(rec) = self.buffer.next() {
+                        return Some(Ok(rec));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProteinRecord {
+    /// With version, e.g. "NP_005219.2".
+    pub accession: String,
+    /// E.g. "epidermal growth factor receptor isoform a precursor".
+    pub definition: String,
+    /// E.g. "Homo sapiens".
+    pub organism: Option<String>,
+    /// One-letter amino acid codes.
+    pub seq: String,
+}
+
+impl ProteinRecord {
+    pub fn seq_aa(&self) -> Vec<AminoAcid> {
+        seq_aa_from_str(&self.seq)
+    }
+}
+
+/// Fetch a protein's sequence, definition line, and organism by accession, e.g. from RefSeq. This
+/// can be used to fill gaps in PDB SEQRES records.
+pub fn get_protein_seq(accession: &str) -> Result<ProteinRecord, ReqError> {
+    let fasta = efetch_fasta(EntrezDb::Protein, &[accession])?;
+
+    let rec = parse_fasta(&fasta)
+        .into_iter()
+        .next()
+        .ok_or(ReqError::NotFound {
+            ident: accession.to_owned(),
+        })?;
+
+    // The header is in the format "NP_005219.2 definition [organism]".
+    let rest = rec.header[rec.accession().len()..].trim();
+    let (definition, organism) = match (rest.rfind('['), rest.ends_with(']')) {
+        (Some(i), true) => (
+            rest[..i].trim().to_owned(),
+            Some(rest[i + 1..rest.len() - 1].to_owned()),
+        ),
+        _ => (rest.to_owned(), None),
+    };
+
+    Ok(ProteinRecord {
+        accession: rec.accession().to_owned(),
+        definition,
+        organism,
+        seq: rec.seq,
+    })
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Spdi {
+    seq_id: String,
+    position: u64,
+    deleted_sequence: String,
+    inserted_sequence: String,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct SnpAlleleInner {
+    spdi: Spdi,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct SnpAlleleRaw {
+    allele: SnpAlleleInner,
+    hgvs: String,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct SeqIdTraits {
+    assembly_name: String,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct PlacementAnnot {
+    seq_id_traits_by_assembly: Vec<SeqIdTraits>,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct PlacementRaw {
+    seq_id: String,
+    /// Primary top-level placement.
+    is_ptlp: bool,
+    placement_annot: PlacementAnnot,
+    alleles: Vec<SnpAlleleRaw>,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct FrequencyRaw {
+    study_name: String,
+    observation: Spdi,
+    allele_count: u64,
+    total_count: u64,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct AssemblyAnnotation {
+    genes: Vec<SnpGene>,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct AlleleAnnotation {
+    frequency: Vec<FrequencyRaw>,
+    assembly_annotation: Vec<AssemblyAnnotation>,
+}
+
+/// Deserializing only
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct PrimarySnapshot {
+    /// E.g. "snv", "delins".
+    variant_type: String,
+    placements_with_allele: Vec<PlacementRaw>,
+    allele_annotations: Vec<AlleleAnnotation>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct RefSnpResp {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    refsnp_id: u64,
+    #[serde(default)]
+    primary_snapshot_data: PrimarySnapshot,
+}
+
+/// An allele at a placement, in SPDI form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnpAllele {
+    pub deleted: String,
+    pub inserted: String,
+    /// E.g. "NC_000008.11:g.19962213C>G".
+    pub hgvs: String,
+}
+
+impl SnpAllele {
+    /// If false, this is the reference allele.
+    pub fn is_variant(&self) -> bool {
+        self.deleted != self.inserted
+    }
+}
+
+/// The position of a SNP on a sequence, e.g. a chromosome, transcript, or protein.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnpPlacement {
+    /// E.g. "NC_000008.11".
+    pub seq_id: String,
+    /// E.g. "GRCh38.p14". Only for genomic placements.
+    pub assembly: Option<String>,
+    /// 0-based.
+    pub position: u64,
+    /// True for the primary placement on the current reference assembly.
+    pub primary: bool,
+    pub alleles: Vec<SnpAllele>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SnpGene {
+    /// NCBI Gene ID.
+    pub id: u32,
+    /// The gene symbol, e.g. "LPL".
+    pub locus: String,
+    pub name: String,
+}
+
+/// An allele's frequency, from one study.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlleleFrequency {
+    /// E.g. "1000Genomes", "GnomAD".
+    pub study: String,
+    pub deleted: String,
+    pub inserted: String,
+    pub allele_count: u64,
+    pub total_count: u64,
+}
+
+impl AlleleFrequency {
+    pub fn frequency(&self) -> f64 {
+        if self.total_count == 0 {
+            return 0.;
+        }
+        self.allele_count as f64 / self.total_count as f64
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Snp {
+    pub rs_id: u64,
+    pub variant_type: String,
+    pub placements: Vec<SnpPlacement>,
+    pub genes: Vec<SnpGene>,
+    pub frequencies: Vec<AlleleFrequency>,
+}
+
+/// Load a dbSNP reference SNP, by rsID, e.g. "rs328", or "328": Alleles and their placements, gene
+/// context, and frequencies. Uses the Variation Services API.
+pub fn get_snp(rs_id: &str) -> Result<Snp, ReqError> {
+    let id = rs_id.trim().to_ascii_lowercase();
+    let id = id.strip_prefix("rs").unwrap_or(&id);
+
+    // A malformed ID can't match anything; don't send it.
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ReqError::NotFound {
+            ident: rs_id.to_owned(),
+        });
+    }
+
+    let url = format!("{VARIATION_URL}/refsnp/{id}");
+
+    let agent = make_agent_ncbi();
+    let mut resp = agent.get(url).call()?;
+
+    if resp.status() == 404 || resp.status() == 400 {
+        return Err(ReqError::NotFound {
+            ident: rs_id.to_owned(),
+        });
+    }
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    let parsed: RefSnpResp = serde_json::from_str(&resp.body_mut().read_to_string()?)?;
+    let snapshot = parsed.primary_snapshot_data;
+
+    let placements = snapshot
+        .placements_with_allele
+        .into_iter()
+        .map(|p| SnpPlacement {
+            position: p
+                .alleles
+                .first()
+                .map(|a| a.allele.spdi.position)
+                .unwrap_or_default(),
+            seq_id: p.seq_id,
+            assembly: p
+                .placement_annot
+                .seq_id_traits_by_assembly
+                .into_iter()
+                .next()
+                .map(|t| t.assembly_name),
+            primary: p.is_ptlp,
+            alleles: p
+                .alleles
+                .into_iter()
+                .map(|a| SnpAllele {
+                    deleted: a.allele.spdi.deleted_sequence,
+                    inserted: a.allele.spdi.inserted_sequence,
+                    hgvs: a.hgvs,
+                })
+                .collect(),
+        })
+        .collect();
+
+    let mut genes: Vec<SnpGene> = Vec::new();
+    let mut frequencies = Vec::new();
+    for annot in snapshot.allele_annotations {
+        for f in annot.frequency {
+            frequencies.push(AlleleFrequency {
+                study: f.study_name,
+                deleted: f.observation.deleted_sequence,
+                inserted: f.observation.inserted_sequence,
+                allele_count: f.allele_count,
+                total_count: f.total_count,
+            });
+        }
+        // Genes are repeated for each allele.
+        for gene in annot.assembly_annotation.into_iter().flat_map(|a| a.genes) {
+            if !genes.iter().any(|g| g.id == gene.id) {
+                genes.push(gene);
+            }
+        }
+    }
+
+    Ok(Snp {
+        rs_id: parsed.refsnp_id,
+        variant_type: snapshot.variant_type,
+        placements,
+        genes,
+        frequencies,
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SraLayout {
+    Single,
+    Paired,
+}
+
+/// Metadata on a sequencing run, in the Sequence Read Archive.
+#[derive(Clone, Debug, Default)]
+pub struct SraRun {
+    /// E.g. "SRR000001".
+    pub run: String,
+    /// E.g. "SRX000001".
+    pub experiment: String,
+    /// E.g. "SRP000001".
+    pub study: String,
+    pub bioproject: Option<String>,
+    /// E.g. "SRS000001".
+    pub sample: Option<String>,
+    pub biosample: Option<String>,
+    pub tax_id: Option<u32>,
+    pub scientific_name: Option<String>,
+    /// E.g. "ILLUMINA", or "OXFORD_NANOPORE".
+    pub platform: String,
+    /// The instrument model, e.g. "Illumina HiSeq 2000".
+    pub model: Option<String>,
+    pub library_name: Option<String>,
+    /// E.g. "WGS", "RNA-Seq", or "AMPLICON".
+    pub library_strategy: Option<String>,
+    /// E.g. "GENOMIC", or "TRANSCRIPTOMIC".
+    pub library_source: Option<String>,
+    /// E.g. "RANDOM", or "PCR".
+    pub library_selection: Option<String>,
+    pub layout: Option<SraLayout>,
+    pub spots: Option<u64>,
+    pub bases: Option<u64>,
+    pub avg_length: Option<u32>,
+    pub size_mb: Option<u64>,
+    pub release_date: Option<String>,
+    /// A URL to the run's SRA-format file.
+    pub download_url: Option<String>,
+}
+
+/// Load run metadata from the Sequence Read Archive, by run, experiment, or study accession. (e.g.
+/// "SRR000001", "SRX000001", or "SRP000001"; ERA and DRA accessions work too.) Returns one entry per
+/// run, e.g. all runs of a study.
+pub fn sra_metadata(accession: &str) -> Result<Vec<SraRun>, ReqError> {
+    let accession = accession.trim();
+
+    let mut url = Url::parse(SRA_RUNINFO_URL).unwrap();
+    url.query_pairs_mut().append_pair("acc", accession);
+
+    let agent = make_agent_ncbi();
+    let mut resp = agent.get(url.as_str()).call()?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+    let text = resp.body_mut().read_to_string()?;
+
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+    let Some(header_line) = lines.next() else {
+        return Err(ReqError::NotFound {
+            ident: accession.to_owned(),
+        });
+    };
+    let header = split_csv_line(header_line);
+
+    let mut result = Vec::new();
+    for line in lines {
+        // The header is repeated for large results.
+        if line == header_line {
+            continue;
+        }
+        let fields = split_csv_line(line);
+
+        let field = |name: &str| -> Option<String> {
+            let i = header.iter().position(|h| h == name)?;
+            let v = fields.get(i)?.trim();
+            if v.is_empty() {
+                None
+            } else {
+                Some(v.to_owned())
+            }
+        };
+        // Values out of range for their field are treated as absent, rather than truncated.
+        let number = |name: &str| field(name).and_then(|v| v.parse::<u64>().ok());
+        let number_u32 = |name: &str| number(name).and_then(|v| u32::try_from(v).ok());
+
+        let layout = match field("LibraryLayout").as_deref() {
+            Some("SINGLE") => Some(SraLayout::Single),
+            Some("PAIRED") => Some(SraLayout::Paired),
+            _ => None,
+        };
+
+        result.push(SraRun {
+            run: field("Run").unwrap_or_default(),
+            experiment: field("Experiment").unwrap_or_default(),
+            study: field("SRAStudy").unwrap_or_default(),
+            bioproject: field("BioProject"),
+            sample: field("Sample"),
+            biosample: field("BioSample"),
+            tax_id: number_u32("TaxID"),
+            scientific_name: field("ScientificName"),
+            platform: field("Platform").unwrap_or_default(),
+            model: field("Model"),
+            library_name: field("LibraryName"),
+            library_strategy: field("LibraryStrategy"),
+            library_source: field("LibrarySource"),
+            library_selection: field("LibrarySelection"),
+            layout,
+            spots: number("spots"),
+            bases: number("bases"),
+            avg_length: number_u32("avgLength"),
+            size_mb: number("size_MB"),
+            release_date: field("ReleaseDate"),
+            download_url: field("download_path"),
+        });
+    }
+
+    if result.is_empty() {
+        return Err(ReqError::NotFound {
+            ident: accession.to_owned(),
+        });
+    }
+
+    Ok(result)
+}
+
 /// Open the web browser to a NCBI-BLAST page, of the sequence of interest.
 ///
 ///Example BLAST
@@ -48,3 +1955,476 @@ pub fn open_blast(seq: &[Nucleotide], seq_name: &str) {
         eprintln!("Failed to open the web browser: {:?}", e);
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BlastProgram {
+    Blastn,
+    /// Blastn, optimized for highly similar sequences.
+    Megablast,
+    Blastp,
+    Blastx,
+    Tblastn,
+    Tblastx,
+}
+
+impl Display for BlastProgram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Blastn | Self::Megablast => "blastn",
+            Self::Blastp => "blastp",
+            Self::Blastx => "blastx",
+            Self::Tblastn => "tblastn",
+            Self::Tblastx => "tblastx",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// Optional BLAST search parameters. Unset ones use the server's defaults.
+/// [Docs](https://blast.ncbi.nlm.nih.gov/doc/blast-help/urlapi.html)
+#[derive(Clone, Debug, Default)]
+pub struct BlastParams {
+    /// E-value threshold.
+    pub expect: Option<f64>,
+    /// The maximum number of hits.
+    pub hitlist_size: Option<u32>,
+    pub word_size: Option<u32>,
+    /// E.g. "BLOSUM62". Protein searches only.
+    pub matrix: Option<String>,
+    /// Low-complexity filter, e.g. "L" to enable, "F" to disable.
+    pub filter: Option<String>,
+    /// Limit the database with an Entrez query, e.g. "txid9606[ORGN]".
+    pub entrez_query: Option<String>,
+}
+
+/// A submitted BLAST search.
+#[derive(Clone, Debug)]
+pub struct Rid {
+    /// Request ID.
+    pub rid: String,
+    /// The server's estimate of the time to completion.
+    pub rtoe: Duration,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BlastStatus {
+    Waiting,
+    Ready {
+        has_hits: bool,
+    },
+    Failed,
+    /// E.g. the RID has expired.
+    Unknown,
+}
+
+/// Get a value from the `QBlastInfo` block of a BLAST response, e.g. "RID = 1A2B3C4D014".
+fn qblast_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines().find_map(|l| {
+        let (k, v) = l.split_once('=')?;
+        (k.trim() == key).then(|| v.trim())
+    })
+}
+
+/// Submit a BLAST search. `sequence` is raw, or FASTA. `database` is e.g. "nt", "nr",
+/// "refseq_protein", "swissprot", or "pdb". Returns the request ID to poll, and retrieve
+/// results with.
+pub fn blast_submit(
+    program: BlastProgram,
+    database: &str,
+    sequence: &str,
+    params: &BlastParams,
+) -> Result<Rid, ReqError> {
+    let program_str = program.to_string();
+    let expect = params.expect.map(|v| v.to_string());
+    let hitlist_size = params.hitlist_size.map(|v| v.to_string());
+    let word_size = params.word_size.map(|v| v.to_string());
+
+    let mut form = vec![
+        ("CMD", "Put"),
+        ("PROGRAM", program_str.as_str()),
+        ("DATABASE", database),
+        ("QUERY", sequence),
+    ];
+    if program == BlastProgram::Megablast {
+        form.push(("MEGABLAST", "on"));
+    }
+    for (k, v) in [
+        ("EXPECT", &expect),
+        ("HITLIST_SIZE", &hitlist_size),
+        ("WORD_SIZE", &word_size),
+        ("MATRIX", &params.matrix),
+        ("FILTER", &params.filter),
+        ("ENTREZ_QUERY", &params.entrez_query),
+    ] {
+        if let Some(v) = v {
+            form.push((k, v));
+        }
+    }
+
+    let agent = make_agent_ncbi();
+    let mut resp = agent.post(BLAST_URL).send_form(form)?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+    let text = resp.body_mut().read_to_string()?;
+
+    let rid = qblast_value(&text, "RID").ok_or(ReqError::Deserialize)?;
+    let rtoe = qblast_value(&text, "RTOE")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    Ok(Rid {
+        rid: rid.to_owned(),
+        rtoe: Duration::from_secs(rtoe),
+    })
+}
+
+/// Check the status of a BLAST search. Per NCBI's guidelines, don't call this more than once a
+/// minute for a given search; [`blast_wait`] handles this.
+pub fn blast_status(rid: &Rid) -> Result<BlastStatus, ReqError> {
+    let mut url = Url::parse(BLAST_URL).unwrap();
+    url.query_pairs_mut()
+        .append_pair("CMD", "Get")
+        .append_pair("FORMAT_OBJECT", "SearchInfo")
+        .append_pair("RID", &rid.rid);
+
+    let agent = make_agent_ncbi();
+    let text = agent
+        .get(url.as_str())
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+
+    Ok(match qblast_value(&text, "Status") {
+        Some("WAITING") => BlastStatus::Waiting,
+        Some("READY") => BlastStatus::Ready {
+            has_hits: qblast_value(&text, "ThereAreHits") == Some("yes"),
+        },
+        Some("FAILED") => BlastStatus::Failed,
+        _ => BlastStatus::Unknown,
+    })
+}
+
+// NCBI asks that we not poll a given search more than once a minute.
+const BLAST_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Block until a BLAST search is complete: Waits for the estimated completion time, then polls
+/// once a minute. Returns whether there are hits. Returns an error of kind `TimedOut` if it's not
+/// complete within `timeout`.
+pub fn blast_wait(rid: &Rid, timeout: Duration) -> Result<bool, ReqError> {
+    let start = Instant::now();
+    // Wait at least a few seconds before the first poll, even if the estimate is 0.
+    let mut delay = rid.rtoe.max(Duration::from_secs(5));
+
+    loop {
+        if start.elapsed() + delay > timeout {
+            return Err(ReqError::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "BLAST search not complete",
+            )));
+        }
+        thread::sleep(delay);
+
+        match blast_status(rid)? {
+            BlastStatus::Ready { has_hits } => return Ok(has_hits),
+            BlastStatus::Waiting => (),
+            BlastStatus::Failed | BlastStatus::Unknown => return Err(ReqError::Http),
+        }
+
+        delay = BLAST_POLL_INTERVAL;
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BlastHitDescription {
+    pub id: String,
+    pub accession: String,
+    pub title: String,
+    pub taxid: Option<u32>,
+    pub sciname: Option<String>,
+}
+
+/// High-scoring segment pair: A local alignment between the query and a hit.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Hsp {
+    pub num: u32,
+    pub bit_score: f64,
+    pub score: f64,
+    pub evalue: f64,
+    /// The number of identical positions.
+    pub identity: u32,
+    pub positive: Option<u32>,
+    /// 1-based, inclusive.
+    pub query_from: u32,
+    pub query_to: u32,
+    pub hit_from: u32,
+    pub hit_to: u32,
+    pub align_len: u32,
+    pub gaps: u32,
+    /// The aligned query sequence, with gaps.
+    pub qseq: String,
+    /// The aligned hit sequence, with gaps.
+    pub hseq: String,
+    pub midline: String,
+}
+
+impl Hsp {
+    /// Percent identity over the alignment.
+    pub fn percent_identity(&self) -> f64 {
+        if self.align_len == 0 {
+            return 0.;
+        }
+        100. * self.identity as f64 / self.align_len as f64
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BlastHit {
+    pub num: u32,
+    /// A hit may be from several identical database sequences.
+    #[serde(rename = "description")]
+    pub descriptions: Vec<BlastHitDescription>,
+    pub len: u32,
+    pub hsps: Vec<Hsp>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BlastSearch {
+    pub query_id: String,
+    pub query_title: Option<String>,
+    pub query_len: u32,
+    pub hits: Vec<BlastHit>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct BlastResults {
+    search: BlastSearch,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct BlastReportRaw {
+    program: String,
+    version: String,
+    results: BlastResults,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct BlastOutput2Item {
+    report: BlastReportRaw,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct BlastOutput2 {
+    #[serde(rename = "BlastOutput2")]
+    items: Vec<BlastOutput2Item>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BlastReport {
+    pub program: String,
+    pub version: String,
+    pub search: BlastSearch,
+}
+
+/// Load the results of a completed BLAST search: Hits, with their alignments and e-values.
+pub fn blast_results(rid: &Rid) -> Result<BlastReport, ReqError> {
+    let mut url = Url::parse(BLAST_URL).unwrap();
+    url.query_pairs_mut()
+        .append_pair("CMD", "Get")
+        .append_pair("FORMAT_TYPE", "JSON2_S")
+        .append_pair("RID", &rid.rid);
+
+    // Results can be large, and slow to transfer.
+    let agent = make_agent_ncbi_download();
+    let resp = agent.get(url.as_str()).call()?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    let parsed: BlastOutput2 = serde_json::from_reader(resp.into_body().into_reader())?;
+    let report = parsed
+        .items
+        .into_iter()
+        .next()
+        .ok_or(ReqError::Deserialize)?
+        .report;
+
+    Ok(BlastReport {
+        program: report.program,
+        version: report.version,
+        search: report.results.search,
+    })
+}
+
+const CD_SEARCH_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A conserved domain hit on a protein sequence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CdHit {
+    /// "Specific", "Non-specific", "Superfamily", or "Multi-dom".
+    pub hit_type: String,
+    pub pssm_id: u32,
+    /// The start of the hit on the query. 1-based, inclusive.
+    pub from: u32,
+    /// The end of the hit on the query. 1-based, inclusive.
+    pub to: u32,
+    pub evalue: f64,
+    pub bit_score: f32,
+    /// E.g. "cd00192", or "pfam07714".
+    pub accession: String,
+    /// E.g. "PTKc".
+    pub short_name: String,
+    /// If the hit is missing part of the domain: "N", "C", or "NC". `None` if complete.
+    pub incomplete: Option<String>,
+    /// The superfamily accession, e.g. "cl21453".
+    pub superfamily: Option<String>,
+}
+
+/// Get a value from a CD-Search response line, e.g. "#cdsid\tQM3-qcdsearch-1234".
+fn cd_search_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines()
+        .filter_map(|l| l.strip_prefix('#'))
+        .filter_map(|l| l.split_once('\t'))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.trim())
+}
+
+/// Parse the tab-separated hits table.
+fn parse_cd_hits(text: &str) -> Vec<CdHit> {
+    let optional = |v: &str| {
+        if v.is_empty() || v == "-" {
+            None
+        } else {
+            Some(v.to_owned())
+        }
+    };
+
+    text.lines()
+        .filter(|l| l.starts_with("Q#"))
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split('\t').map(|c| c.trim()).collect();
+            if cols.len() < 9 {
+                return None;
+            }
+            Some(CdHit {
+                hit_type: cols[1].to_owned(),
+                pssm_id: cols[2].parse().ok()?,
+                from: cols[3].parse().ok()?,
+                to: cols[4].parse().ok()?,
+                evalue: cols[5].parse().ok()?,
+                bit_score: cols[6].parse().ok()?,
+                accession: cols[7].to_owned(),
+                short_name: cols[8].to_owned(),
+                incomplete: cols.get(9).and_then(|v| optional(v)),
+                superfamily: cols.get(10).and_then(|v| optional(v)),
+            })
+        })
+        .collect()
+}
+
+/// Find conserved domains on a protein sequence, using the Batch CD-Search service. `sequence` is
+/// raw, or FASTA. Submits the search, and blocks, polling until complete. Returns an error of kind
+/// `TimedOut` if it's not complete within `timeout`.
+/// [Docs](https://www.ncbi.nlm.nih.gov/Structure/cdd/cdd_help.shtml#BatchRPSBWebAPI)
+pub fn cd_search(sequence: &str, timeout: Duration) -> Result<Vec<CdHit>, ReqError> {
+    let agent = make_agent_ncbi();
+    let start = Instant::now();
+
+    let form = [
+        ("queries", sequence),
+        ("db", "cdd"),
+        ("smode", "auto"),
+        ("useid1", "true"),
+        ("compbasedadj", "1"),
+        ("filter", "true"),
+        ("evalue", "0.01"),
+        ("maxhit", "500"),
+        ("dmode", "rep"),
+        ("tdata", "hits"),
+    ];
+
+    let mut resp = agent.post(CD_SEARCH_URL).send_form(form)?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+    let text = resp.body_mut().read_to_string()?;
+
+    let cdsid = cd_search_value(&text, "cdsid").ok_or(ReqError::Deserialize)?;
+
+    let mut url = Url::parse(CD_SEARCH_URL).unwrap();
+    url.query_pairs_mut()
+        .append_pair("cdsid", cdsid)
+        .append_pair("tdata", "hits")
+        .append_pair("dmode", "rep");
+
+    loop {
+        if start.elapsed() + CD_SEARCH_POLL_INTERVAL > timeout {
+            return Err(ReqError::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "CD-Search not complete",
+            )));
+        }
+        thread::sleep(CD_SEARCH_POLL_INTERVAL);
+
+        let mut resp = agent.get(url.as_str()).call()?;
+        if resp.status() != 200 {
+            return Err(ReqError::Http);
+        }
+        let text = resp.body_mut().read_to_string()?;
+
+        // 0: Complete. 3: Running. Others are errors.
+        match cd_search_value(&text, "status") {
+            Some("0") => return Ok(parse_cd_hits(&text)),
+            Some("3") => (),
+            _ => return Err(ReqError::Http),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CD_HITS: &str = "#Batch CD-search tool\tNIH/NLM/NCBI
+#cdsid\tQM3-qcdsearch-1A2B3C4D5E6F7-8G9H
+#datatype\thitsConcise Results
+#status\t0
+
+Query\tHit type\tPSSM-ID\tFrom\tTo\tE-Value\tBitscore\tAccession\tShort name\tIncomplete\tSuperfamily
+Q#1 - >sp|P00533|EGFR_HUMAN\tSpecific\t270631\t712\t979\t0\t580.104\tcd05108\tPTKc_EGFR\t-\tcl21453
+Q#1 - >sp|P00533|EGFR_HUMAN\tNon-specific\t214569\t57\t167\t1.23e-33\t125.8\tpfam01030\tRecep_L_domain\tC\t-
+Q#1 - >sp|P00533|EGFR_HUMAN\tSuperfamily\tnot-a-number\t1\t2\t1\t1\tcl00000\tBad
+";
+
+    #[test]
+    fn cd_hits() {
+        assert_eq!(
+            cd_search_value(CD_HITS, "cdsid"),
+            Some("QM3-qcdsearch-1A2B3C4D5E6F7-8G9H")
+        );
+
+        let hits = parse_cd_hits(CD_HITS);
+        assert_eq!(hits.len(), 2);
+
+        assert_eq!(hits[0].hit_type, "Specific");
+        assert_eq!(hits[0].pssm_id, 270631);
+        assert_eq!((hits[0].from, hits[0].to), (712, 979));
+        assert_eq!(hits[0].evalue, 0.);
+        assert_eq!(hits[0].short_name, "PTKc_EGFR");
+        assert_eq!(hits[0].incomplete, None);
+        assert_eq!(hits[0].superfamily.as_deref(), Some("cl21453"));
+
+        assert_eq!(hits[1].evalue, 1.23e-33);
+        assert_eq!(hits[1].accession, "pfam01030");
+        assert_eq!(hits[1].incomplete.as_deref(), Some("C"));
+        assert_eq!(hits[1].superfamily, None);
+    }
+}
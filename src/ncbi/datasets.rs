@@ -0,0 +1,422 @@
+//! Typed access to the NCBI Datasets v2 API: Genome assembly and gene metadata, and data packages.
+//! [Docs](https://www.ncbi.nlm.nih.gov/datasets/docs/v2/api/rest-api/)
+
+use std::{
+    fmt::{Display, Formatter},
+    io::Read,
+};
+
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_aux::prelude::{deserialize_number_from_string, deserialize_option_number_from_string};
+use url::Url;
+
+use crate::{
+    ReqError,
+    ncbi::{make_agent_ncbi, make_agent_ncbi_download},
+};
+
+const DATASETS_URL: &str = "https://api.ncbi.nlm.nih.gov/datasets/v2";
+
+/// The number of reports to request per page. This is the maximum the API allows.
+const PAGE_SIZE: u32 = 1_000;
+
+/// File types to include in a genome assembly data package.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GenomeAnnotationType {
+    GenomeFasta,
+    GenomeGff,
+    GenomeGbff,
+    GenomeGtf,
+    ProtFasta,
+    RnaFasta,
+    CdsFasta,
+    SequenceReport,
+}
+
+impl Display for GenomeAnnotationType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::GenomeFasta => "GENOME_FASTA",
+            Self::GenomeGff => "GENOME_GFF",
+            Self::GenomeGbff => "GENOME_GBFF",
+            Self::GenomeGtf => "GENOME_GTF",
+            Self::ProtFasta => "PROT_FASTA",
+            Self::RnaFasta => "RNA_FASTA",
+            Self::CdsFasta => "CDS_FASTA",
+            Self::SequenceReport => "SEQUENCE_REPORT",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// File types to include in a gene data package.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GeneAnnotationType {
+    FastaGene,
+    FastaRna,
+    FastaProtein,
+    FastaCds,
+    Fasta5pUtr,
+    Fasta3pUtr,
+}
+
+impl Display for GeneAnnotationType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::FastaGene => "FASTA_GENE",
+            Self::FastaRna => "FASTA_RNA",
+            Self::FastaProtein => "FASTA_PROTEIN",
+            Self::FastaCds => "FASTA_CDS",
+            Self::Fasta5pUtr => "FASTA_5P_UTR",
+            Self::Fasta3pUtr => "FASTA_3P_UTR",
+        };
+        write!(f, "{v}")
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DatasetsOrganism {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub tax_id: u32,
+    /// E.g. "Homo sapiens".
+    pub organism_name: String,
+    pub common_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BioSample {
+    pub accession: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssemblyInfo {
+    /// E.g. "Complete Genome", "Chromosome", "Scaffold", "Contig".
+    pub assembly_level: String,
+    /// E.g. "current", "previous".
+    pub assembly_status: String,
+    /// E.g. "GRCh38.p14".
+    pub assembly_name: String,
+    pub assembly_type: Option<String>,
+    /// E.g. "reference genome".
+    pub refseq_category: Option<String>,
+    pub release_date: Option<String>,
+    pub submitter: Option<String>,
+    pub bioproject_accession: Option<String>,
+    pub biosample: Option<BioSample>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssemblyStats {
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub total_number_of_chromosomes: Option<u32>,
+    /// In base pairs.
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub total_sequence_length: Option<u64>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub total_ungapped_length: Option<u64>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub number_of_contigs: Option<u32>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub contig_n50: Option<u64>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub contig_l50: Option<u32>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub number_of_scaffolds: Option<u32>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub scaffold_n50: Option<u64>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub scaffold_l50: Option<u32>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub gc_percent: Option<f32>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AnnotationInfo {
+    pub name: Option<String>,
+    /// E.g. "NCBI RefSeq".
+    pub provider: Option<String>,
+    pub release_date: Option<String>,
+}
+
+/// Metadata on a genome assembly.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssemblyReport {
+    /// E.g. "GCF_000001405.40".
+    pub accession: String,
+    pub current_accession: Option<String>,
+    /// The equivalent accession in the other of GenBank (GCA) or RefSeq (GCF).
+    pub paired_accession: Option<String>,
+    /// E.g. "SOURCE_DATABASE_REFSEQ".
+    pub source_database: Option<String>,
+    pub organism: DatasetsOrganism,
+    pub assembly_info: AssemblyInfo,
+    pub assembly_stats: AssemblyStats,
+    pub annotation_info: Option<AnnotationInfo>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DatasetsRange {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub begin: u64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub end: u64,
+    /// "plus", or "minus".
+    pub orientation: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GenomicLocation {
+    /// E.g. "NC_000017.11".
+    pub genomic_accession_version: String,
+    /// E.g. "17".
+    pub sequence_name: Option<String>,
+    pub genomic_range: DatasetsRange,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GeneAnnotation {
+    pub assembly_accession: Option<String>,
+    pub assembly_name: Option<String>,
+    pub release_name: Option<String>,
+    pub genomic_locations: Vec<GenomicLocation>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GeneSummaryText {
+    pub description: String,
+    pub source: Option<String>,
+}
+
+/// Metadata on a gene.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GeneReport {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub gene_id: u32,
+    pub symbol: String,
+    pub description: Option<String>,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub tax_id: u32,
+    /// E.g. "Homo sapiens".
+    pub taxname: String,
+    pub common_name: Option<String>,
+    /// E.g. "PROTEIN_CODING".
+    #[serde(rename = "type")]
+    pub gene_type: Option<String>,
+    pub orientation: Option<String>,
+    pub chromosomes: Vec<String>,
+    pub synonyms: Vec<String>,
+    pub swiss_prot_accessions: Vec<String>,
+    pub ensembl_gene_ids: Vec<String>,
+    pub summary: Vec<GeneSummaryText>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub transcript_count: Option<u32>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub protein_count: Option<u32>,
+    pub annotations: Vec<GeneAnnotation>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct GeneReportWrapper {
+    gene: GeneReport,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+struct ReportsPage<T> {
+    #[serde(default = "Vec::new")]
+    reports: Vec<T>,
+    next_page_token: Option<String>,
+}
+
+fn datasets_url(path: &str, params: &[(&str, &str)]) -> Url {
+    let mut url = Url::parse(&format!("{DATASETS_URL}/{path}")).unwrap();
+    {
+        let mut pairs = url.query_pairs_mut();
+        for (k, v) in params {
+            pairs.append_pair(k, v);
+        }
+    }
+    url
+}
+
+/// Load all pages of a report endpoint.
+fn get_reports<T: DeserializeOwned>(
+    path: &str,
+    params: &[(&str, &str)],
+) -> Result<Vec<T>, ReqError> {
+    let agent = make_agent_ncbi();
+    let page_size = PAGE_SIZE.to_string();
+
+    let mut result = Vec::new();
+    let mut page_token: Option<String> = None;
+
+    loop {
+        let mut params_page = params.to_vec();
+        params_page.push(("page_size", &page_size));
+        if let Some(token) = &page_token {
+            params_page.push(("page_token", token));
+        }
+
+        let mut resp = agent
+            .get(datasets_url(path, &params_page).as_str())
+            .call()?;
+        if resp.status() == 404 {
+            return Err(ReqError::NotFound {
+                ident: path.to_owned(),
+            });
+        }
+        if resp.status() != 200 {
+            return Err(ReqError::Http);
+        }
+
+        let page: ReportsPage<T> = serde_json::from_str(&resp.body_mut().read_to_string()?)?;
+        result.extend(page.reports);
+
+        match page.next_page_token {
+            Some(token) if !token.is_empty() => page_token = Some(token),
+            _ => break,
+        }
+    }
+
+    Ok(result)
+}
+
+/// Download a data package, as ZIP bytes.
+fn download_package(path: &str, params: &[(&str, &str)]) -> Result<Vec<u8>, ReqError> {
+    let agent = make_agent_ncbi_download();
+
+    let resp = agent.get(datasets_url(path, params).as_str()).call()?;
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound {
+            ident: path.to_owned(),
+        });
+    }
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    // These may be large; don't use the size-limited reader.
+    let mut result = Vec::new();
+    resp.into_body().into_reader().read_to_end(&mut result)?;
+
+    Ok(result)
+}
+
+fn join<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Load metadata on genome assemblies, by accession, e.g. "GCF_000001405.40".
+pub fn assembly_reports(accessions: &[&str]) -> Result<Vec<AssemblyReport>, ReqError> {
+    get_reports(
+        &format!("genome/accession/{}/dataset_report", accessions.join(",")),
+        &[],
+    )
+}
+
+/// Load metadata on the genome assemblies for a taxon, by NCBI Taxonomy ID, or name, e.g. "9606" or
+/// "Homo sapiens". If `reference_only` is true, only return reference genomes. Otherwise, this may
+/// return thousands of assemblies for well-studied organisms.
+pub fn assembly_reports_by_taxon(
+    taxon: &str,
+    reference_only: bool,
+) -> Result<Vec<AssemblyReport>, ReqError> {
+    let mut params = Vec::new();
+    if reference_only {
+        params.push(("filters.reference_only", "true"));
+    }
+
+    get_reports(&format!("genome/taxon/{taxon}/dataset_report"), &params)
+}
+
+/// Download a genome assembly data package, as ZIP bytes. If `dehydrated` is true, the package
+/// contains only metadata, and a fetch.txt of URLs to the data files, for later retrieval.
+pub fn download_assembly(
+    accessions: &[&str],
+    include: &[GenomeAnnotationType],
+    dehydrated: bool,
+) -> Result<Vec<u8>, ReqError> {
+    let include = join(include);
+
+    let mut params = vec![("include_annotation_type", include.as_str())];
+    if dehydrated {
+        params.push(("hydrated", "DATA_REPORT_ONLY"));
+    }
+
+    download_package(
+        &format!("genome/accession/{}/download", accessions.join(",")),
+        &params,
+    )
+}
+
+/// Load metadata on genes, by NCBI Gene ID.
+pub fn gene_reports(gene_ids: &[u32]) -> Result<Vec<GeneReport>, ReqError> {
+    let reports: Vec<GeneReportWrapper> =
+        get_reports(&format!("gene/id/{}/dataset_report", join(gene_ids)), &[])?;
+
+    Ok(reports.into_iter().map(|r| r.gene).collect())
+}
+
+/// Load metadata on genes, by symbol, e.g. "BRCA1", for a taxon, by NCBI Taxonomy ID, or name.
+pub fn gene_reports_by_symbol(symbols: &[&str], taxon: &str) -> Result<Vec<GeneReport>, ReqError> {
+    let reports: Vec<GeneReportWrapper> = get_reports(
+        &format!(
+            "gene/symbol/{}/taxon/{taxon}/dataset_report",
+            symbols.join(",")
+        ),
+        &[],
+    )?;
+
+    Ok(reports.into_iter().map(|r| r.gene).collect())
+}
+
+/// Download a gene data package, as ZIP bytes.
+pub fn download_gene(
+    gene_ids: &[u32],
+    include: &[GeneAnnotationType],
+) -> Result<Vec<u8>, ReqError> {
+    let include = join(include);
+
+    download_package(
+        &format!("gene/id/{}/download", join(gene_ids)),
+        &[("include_annotation_type", include.as_str())],
+    )
+}
+
+/// Download a gene data package, as ZIP bytes, by symbol, e.g. "BRCA1", for a taxon, by NCBI
+/// Taxonomy ID, or name. Symbols are resolved to Gene IDs first; returns `NotFound` if none match.
+pub fn download_gene_by_symbol(
+    symbols: &[&str],
+    taxon: &str,
+    include: &[GeneAnnotationType],
+) -> Result<Vec<u8>, ReqError> {
+    let gene_ids: Vec<_> = gene_reports_by_symbol(symbols, taxon)?
+        .into_iter()
+        .map(|g| g.gene_id)
+        .collect();
+
+    if gene_ids.is_empty() {
+        return Err(ReqError::NotFound {
+            ident: format!("{} ({taxon})", symbols.join(",")),
+        });
+    }
+
+    download_gene(&gene_ids, include)
+}
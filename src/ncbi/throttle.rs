@@ -0,0 +1,83 @@
+//! Client-side request throttling, and API key handling for NCBI. NCBI limits clients to 3 requests
+//! per second, or 10 with an API key. We space out requests accordingly, so batch jobs aren't
+//! blocked.
+//! [Docs](https://www.ncbi.nlm.nih.gov/books/NBK25497/#chapter2.Usage_Guidelines_and_Requiremen)
+
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use ureq::{
+    Body, SendBody,
+    http::{Request, Response, Uri},
+    middleware::MiddlewareNext,
+};
+
+use super::api_key;
+
+/// No more than 3 requests per second, without an API key.
+const MIN_INTERVAL: Duration = Duration::from_millis(334);
+/// No more than 10 requests per second, with one.
+const MIN_INTERVAL_API_KEY: Duration = Duration::from_millis(100);
+
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Block until we can make the next request. We reserve a time slot under the lock, then sleep
+/// after releasing it, so concurrent requests are spaced out, without serializing on the lock.
+fn wait_turn(interval: Duration) {
+    let scheduled = {
+        let mut last_request = LAST_REQUEST.lock().unwrap();
+
+        let now = Instant::now();
+        let scheduled = match *last_request {
+            Some(last) => (last + interval).max(now),
+            None => now,
+        };
+
+        *last_request = Some(scheduled);
+        scheduled
+    };
+
+    let now = Instant::now();
+    if scheduled > now {
+        thread::sleep(scheduled - now);
+    }
+}
+
+/// Add the `api_key` query parameter to a URI.
+fn with_api_key(uri: &Uri, key: &str) -> Option<Uri> {
+    let mut url = url::Url::parse(&uri.to_string()).ok()?;
+    url.query_pairs_mut().append_pair("api_key", key);
+
+    url.as_str().parse().ok()
+}
+
+/// Middleware for NCBI agents, adding the API key if set, and applying the throttle to each
+/// request.
+pub(crate) fn middleware(
+    req: Request<SendBody>,
+    next: MiddlewareNext,
+) -> Result<Response<Body>, ureq::Error> {
+    let key = api_key();
+
+    let req = match &key {
+        Some(k) => {
+            let (mut parts, body) = req.into_parts();
+            if let Some(uri) = with_api_key(&parts.uri, k) {
+                parts.uri = uri;
+            }
+            Request::from_parts(parts, body)
+        }
+        None => req,
+    };
+
+    wait_turn(if key.is_some() {
+        MIN_INTERVAL_API_KEY
+    } else {
+        MIN_INTERVAL
+    });
+
+    next.handle(req)
+}
@@ -1,14 +1,21 @@
 //! [Home page](https://www.ebi.ac.uk/pdbe/)
 //! [API docs](https://www.ebi.ac.uk/pdbe/api/)
 
-use std::collections::HashMap;
+use std::{collections::HashMap, io::Read};
 
-use serde::Deserialize;
+use serde::{Deserialize, de::DeserializeOwned};
+use url::Url;
 
-use crate::{ReqError, make_agent};
+use crate::{ReqError, make_agent, make_agent_download, ncbi::parse_fasta};
 
 const BASE_URL: &str = "https://www.ebi.ac.uk/pdbe-srv/pdbechem/chemicalCompound/show";
 const MAPPINGS_URL: &str = "https://www.ebi.ac.uk/pdbe/api/mappings";
+const ENTRY_URL: &str = "https://www.ebi.ac.uk/pdbe/api/pdb/entry";
+const COMPOUND_URL: &str = "https://www.ebi.ac.uk/pdbe/api/pdb/compound";
+const ENTRY_FILES_URL: &str = "https://www.ebi.ac.uk/pdbe/entry-files";
+const FASTA_URL: &str = "https://www.ebi.ac.uk/pdbe/entry/pdb";
+const SEARCH_URL: &str = "https://www.ebi.ac.uk/pdbe/search/pdb/select";
+const VALIDATION_URL: &str = "https://www.ebi.ac.uk/pdbe/api/validation";
 
 // ---- SIFTS / UniProt mapping types ----------------------------------------
 
@@ -71,6 +78,81 @@ struct RawUniprotSection {
     uniprot: HashMap<String, RawUniprotEntry>,
 }
 
+/// One contiguous segment of a domain or family mapped onto a PDB chain. Unlike [`SiftsMapping`],
+/// UniProt numbering is only present for some resources.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SiftsSegment {
+    pub entity_id: u32,
+    pub chain_id: String,
+    pub struct_asym_id: String,
+    pub start: SiftsResiduePosition,
+    pub end: SiftsResiduePosition,
+    #[serde(default)]
+    pub unp_start: Option<u32>,
+    #[serde(default)]
+    pub unp_end: Option<u32>,
+    /// CATH domain ID, e.g. `"1cbsA00"`.
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// SCOP domain ID, e.g. `"d1cbsa_"`.
+    #[serde(default)]
+    pub scop_id: Option<String>,
+}
+
+/// All SIFTS mappings for one domain or family (e.g. a Pfam accession) within a PDB structure.
+#[derive(Clone, Debug)]
+pub struct SiftsDomainMapping {
+    /// E.g. `"PF00061"` for Pfam, `"2.40.128.20"` for CATH, or `"IPR000463"` for InterPro.
+    pub accession: String,
+    pub identifier: Option<String>,
+    pub description: Option<String>,
+    pub mappings: Vec<SiftsSegment>,
+}
+
+/// SIFTS mappings of a PDB structure to sequence, family, and domain databases.
+#[derive(Clone, Debug, Default)]
+pub struct SiftsMappings {
+    pub uniprot: Vec<SiftsUniprotMapping>,
+    pub pfam: Vec<SiftsDomainMapping>,
+    pub cath: Vec<SiftsDomainMapping>,
+    pub scop: Vec<SiftsDomainMapping>,
+    pub interpro: Vec<SiftsDomainMapping>,
+}
+
+#[derive(Deserialize)]
+struct RawDomainEntry {
+    #[serde(default)]
+    identifier: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    mappings: Vec<SiftsSegment>,
+}
+
+#[derive(Deserialize)]
+struct RawDomainSections {
+    #[serde(rename = "Pfam", default)]
+    pfam: HashMap<String, RawDomainEntry>,
+    #[serde(rename = "CATH", default)]
+    cath: HashMap<String, RawDomainEntry>,
+    #[serde(rename = "SCOP", default)]
+    scop: HashMap<String, RawDomainEntry>,
+    #[serde(rename = "InterPro", default)]
+    interpro: HashMap<String, RawDomainEntry>,
+}
+
+fn domain_mappings(raw: HashMap<String, RawDomainEntry>) -> Vec<SiftsDomainMapping> {
+    raw.into_iter()
+        .map(|(accession, entry)| SiftsDomainMapping {
+            accession,
+            identifier: entry.identifier,
+            description: entry.description.or(entry.name),
+            mappings: entry.mappings,
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 
 /// Fetch SIFTS UniProt–PDB residue-level mappings for a given PDB entry.
@@ -102,6 +184,518 @@ pub fn load_uniprot_mappings(pdb_id: &str) -> Result<Vec<SiftsUniprotMapping>, R
         .collect())
 }
 
+/// Load an API resource, which is keyed by the identifier queried.
+fn get_keyed<T: DeserializeOwned>(base_url: &str, ident: &str) -> Result<T, ReqError> {
+    get_keyed_url(&format!("{base_url}/{ident}"), ident)
+}
+
+/// As `get_keyed`, for resources where the URL has more than the identifier, e.g. a chain.
+fn get_keyed_url<T: DeserializeOwned>(url: &str, ident: &str) -> Result<T, ReqError> {
+    let agent = make_agent();
+
+    let mut resp = agent.get(url).call()?;
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound {
+            ident: ident.to_owned(),
+        });
+    }
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    let mut raw: HashMap<String, T> = serde_json::from_str(&resp.body_mut().read_to_string()?)?;
+    raw.remove(ident).ok_or(ReqError::Deserialize)
+}
+
+/// Load a per-entry API resource. These are keyed by the (lowercase) PDB ID.
+fn get_entry<T: DeserializeOwned>(base_url: &str, pdb_id: &str) -> Result<T, ReqError> {
+    get_keyed(base_url, &pdb_id.trim().to_lowercase())
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct EntitySource {
+    pub organism_scientific_name: Option<String>,
+    pub tax_id: Option<u32>,
+    /// The organism the molecule was expressed in, if engineered; e.g. "Escherichia coli".
+    pub expression_host_scientific_name: Option<String>,
+    pub expression_host_tax_id: Option<u32>,
+}
+
+/// An entity (distinct molecule) of an entry: A polymer, ligand, or water.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PdbeEntity {
+    pub entity_id: u32,
+    /// E.g. "polypeptide(L)", "polydeoxyribonucleotide", "bound", or "water".
+    pub molecule_type: String,
+    pub molecule_name: Vec<String>,
+    /// One-letter sequence, for polymers.
+    pub sequence: Option<String>,
+    pub length: Option<u32>,
+    /// Author chain IDs.
+    pub in_chains: Vec<String>,
+    /// Label (mmCIF) chain IDs.
+    pub in_struct_asyms: Vec<String>,
+    /// Chemical component IDs, for ligands; e.g. "RET".
+    pub chem_comp_ids: Vec<String>,
+    pub source: Vec<EntitySource>,
+    pub number_of_copies: u32,
+    /// In Da.
+    pub weight: Option<f32>,
+    pub gene_name: Option<Vec<String>>,
+}
+
+impl PdbeEntity {
+    pub fn is_polymer(&self) -> bool {
+        self.molecule_type.to_lowercase().starts_with("poly")
+    }
+}
+
+/// Load the entities of an entry: Polymers with their sequences and chains, ligands, and water.
+pub fn get_molecules(pdb_id: &str) -> Result<Vec<PdbeEntity>, ReqError> {
+    get_entry(&format!("{ENTRY_URL}/molecules"), pdb_id)
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssemblyEntity {
+    pub entity_id: u32,
+    /// E.g. "polypeptide(L)", or "bound".
+    pub molecule_type: String,
+    pub molecule_name: Vec<String>,
+    pub number_of_copies: u32,
+    /// Author chain IDs.
+    pub in_chains: Vec<String>,
+}
+
+/// A biological assembly of an entry.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Assembly {
+    /// E.g. "1". This matches the number in assembly file names.
+    pub assembly_id: String,
+    /// E.g. "monomer", or "tetramer".
+    pub name: String,
+    /// E.g. "homo", or "hetero".
+    pub form: String,
+    /// If this is PDBe's preferred assembly for the entry.
+    pub preferred: bool,
+    /// In kDa.
+    pub molecular_weight: Option<f32>,
+    /// E.g. "author_and_software_defined_assembly".
+    pub details: Option<String>,
+    pub entities: Vec<AssemblyEntity>,
+}
+
+/// Load the biological assemblies of an entry, with their composition.
+pub fn get_assemblies(pdb_id: &str) -> Result<Vec<Assembly>, ReqError> {
+    get_entry(&format!("{ENTRY_URL}/assembly"), pdb_id)
+}
+
+/// Load the preferred biological assembly of an entry, if one is flagged.
+pub fn get_preferred_assembly(pdb_id: &str) -> Result<Option<Assembly>, ReqError> {
+    Ok(get_assemblies(pdb_id)?.into_iter().find(|a| a.preferred))
+}
+
+/// A SMILES string, and the program that generated it. In the API response, these are of the form
+/// `{"name": "Nc1ncnc2...", "program": "OpenEye OEToolkits", "program_version": "1.7.6"}`; the
+/// same shape as systematic names.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CompoundSmiles {
+    #[serde(rename = "name")]
+    pub smiles: String,
+    /// E.g. "CACTVS", "OpenEye OEToolkits".
+    pub program: String,
+    pub program_version: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SystematicName {
+    pub name: String,
+    /// E.g. "ACDLabs".
+    pub program: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CrossLink {
+    /// E.g. "PubChem", "ChEMBL", "DrugBank".
+    pub resource: String,
+    pub resource_id: String,
+}
+
+/// A chemical component, from the PDBe Chemical Component Dictionary.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Compound {
+    /// E.g. "ADENOSINE-5'-TRIPHOSPHATE".
+    pub name: String,
+    /// E.g. "C10 H16 N5 O13 P3".
+    pub formula: Option<String>,
+    /// In Da.
+    pub weight: Option<f32>,
+    pub formal_charge: Option<i8>,
+    pub inchi: Option<String>,
+    pub inchi_key: Option<String>,
+    pub smiles: Vec<CompoundSmiles>,
+    pub systematic_names: Vec<SystematicName>,
+    pub synonyms: Vec<String>,
+    pub cross_links: Vec<CrossLink>,
+    /// E.g. "non-polymer", "L-peptide linking".
+    pub chem_comp_type: Option<String>,
+}
+
+impl Compound {
+    /// The first non-empty SMILES string.
+    pub fn smiles(&self) -> Option<&str> {
+        self.smiles
+            .iter()
+            .find(|s| !s.smiles.is_empty())
+            .map(|s| s.smiles.as_str())
+    }
+
+    /// IDs from a cross-referenced resource, e.g. "PubChem".
+    pub fn cross_link_ids(&self, resource: &str) -> Vec<&str> {
+        self.cross_links
+            .iter()
+            .filter(|c| c.resource.eq_ignore_ascii_case(resource))
+            .map(|c| c.resource_id.as_str())
+            .collect()
+    }
+}
+
+/// Load data on a chemical component, by CCD ID, e.g. "ATP".
+pub fn get_compound(ccd_id: &str) -> Result<Compound, ReqError> {
+    let ccd_id = ccd_id.trim().to_uppercase();
+
+    let compounds: Vec<Compound> = get_keyed(&format!("{COMPOUND_URL}/summary"), &ccd_id)?;
+    compounds
+        .into_iter()
+        .next()
+        .ok_or(ReqError::NotFound { ident: ccd_id })
+}
+
+/// Fetch SIFTS mappings for a given PDB entry: UniProt (as in [`load_uniprot_mappings`]), Pfam,
+/// CATH, SCOP, and InterPro segments, with residue ranges in PDB numbering, and where available,
+/// UniProt numbering. Use these to transfer sequence annotations onto structures.
+///
+/// API: `https://www.ebi.ac.uk/pdbe/api/mappings/{pdb_id}`
+pub fn get_sifts_mappings(pdb_id: &str) -> Result<SiftsMappings, ReqError> {
+    let domains: RawDomainSections = get_entry(MAPPINGS_URL, pdb_id)?;
+
+    Ok(SiftsMappings {
+        uniprot: load_uniprot_mappings(pdb_id)?,
+        pfam: domain_mappings(domains.pfam),
+        cath: domain_mappings(domains.cath),
+        scop: domain_mappings(domains.scop),
+        interpro: domain_mappings(domains.interpro),
+    })
+}
+
+/// A residue, as referenced by binding site and modified residue records.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SiteResidue {
+    pub entity_id: u32,
+    /// Author chain ID.
+    pub chain_id: String,
+    /// Label (mmCIF) chain ID.
+    pub struct_asym_id: String,
+    /// Sequential (mmCIF label) residue number. Absent for non-polymer residues.
+    pub residue_number: Option<i32>,
+    pub author_residue_number: Option<i32>,
+    pub author_insertion_code: Option<String>,
+    /// E.g. "RET", or "ARG".
+    pub chem_comp_id: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BindingSite {
+    /// E.g. "AC1".
+    pub site_id: String,
+    /// E.g. "software", or "author".
+    pub evidence_code: String,
+    /// E.g. "BINDING SITE FOR RESIDUE REA A 200".
+    pub details: String,
+    /// Residues in contact with the ligand.
+    pub site_residues: Vec<SiteResidue>,
+    pub ligand_residues: Vec<SiteResidue>,
+}
+
+/// Load the ligand binding sites of an entry, with the residues in contact with each ligand.
+pub fn get_binding_sites(pdb_id: &str) -> Result<Vec<BindingSite>, ReqError> {
+    get_entry(&format!("{ENTRY_URL}/binding_sites"), pdb_id)
+}
+
+/// An observed or unobserved residue of a polymer chain.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ListedResidue {
+    /// Sequential (mmCIF label) residue number.
+    pub residue_number: i32,
+    pub author_residue_number: Option<i32>,
+    pub author_insertion_code: Option<String>,
+    /// E.g. "ARG", or "MSE".
+    pub residue_name: String,
+    /// The fraction of atoms of this residue present in the model. 0 for residues not modelled.
+    pub observed_ratio: f32,
+    pub multiple_conformers: Option<serde_json::Value>,
+    /// If this is a modified residue, e.g. selenomethionine. Not part of the API response; set
+    /// from the entry's modified residue listing.
+    #[serde(skip)]
+    pub modified: bool,
+}
+
+impl ListedResidue {
+    pub fn is_observed(&self) -> bool {
+        self.observed_ratio > 0.
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ListedChain {
+    /// Author chain ID.
+    pub chain_id: String,
+    /// Label (mmCIF) chain ID.
+    pub struct_asym_id: String,
+    pub residues: Vec<ListedResidue>,
+}
+
+#[derive(Deserialize)]
+struct ResidueListingEntity {
+    #[serde(default)]
+    chains: Vec<ListedChain>,
+}
+
+#[derive(Deserialize)]
+struct ResidueListingResp {
+    #[serde(default)]
+    molecules: Vec<ResidueListingEntity>,
+}
+
+/// Load the residues of a chain, with their label and author numbering, whether they're observed
+/// in the model, and whether they're modified.
+pub fn get_residue_listing(pdb_id: &str, chain: &str) -> Result<ListedChain, ReqError> {
+    let id = pdb_id.trim().to_lowercase();
+
+    let resp: ResidueListingResp = get_keyed_url(
+        &format!("{ENTRY_URL}/residue_listing/{id}/chain/{}", chain.trim()),
+        &id,
+    )?;
+
+    let mut result = resp
+        .molecules
+        .into_iter()
+        .flat_map(|m| m.chains)
+        .find(|c| c.chain_id == chain.trim())
+        .ok_or_else(|| ReqError::NotFound {
+            ident: format!("{id} chain {chain}"),
+        })?;
+
+    // This returns 404 for entries with no modified residues.
+    let modified =
+        match get_entry::<Vec<SiteResidue>>(&format!("{ENTRY_URL}/modified_AA_or_NA"), &id) {
+            Ok(r) => r,
+            Err(ReqError::NotFound { .. }) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+    for res in &mut result.residues {
+        res.modified = modified
+            .iter()
+            .any(|m| m.chain_id == result.chain_id && m.residue_number == Some(res.residue_number));
+    }
+
+    Ok(result)
+}
+
+/// A filter query for `search`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchFilter {
+    /// E.g. "X-ray diffraction", or "Electron Microscopy".
+    ExperimentalMethod(String),
+    /// Scientific name, e.g. "Homo sapiens".
+    Organism(String),
+    /// In Å.
+    MaxResolution(f32),
+    /// An arbitrary Solr field and value, e.g. `("tax_id", "9606")`.
+    Field(String, String),
+}
+
+impl SearchFilter {
+    fn to_fq(&self) -> String {
+        match self {
+            Self::ExperimentalMethod(v) => format!("experimental_method:\"{v}\""),
+            Self::Organism(v) => format!("organism_scientific_name:\"{v}\""),
+            Self::MaxResolution(v) => format!("resolution:[* TO {v}]"),
+            Self::Field(k, v) => format!("{k}:\"{v}\""),
+        }
+    }
+}
+
+/// A document from the PDBe search; one per entity.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchDoc {
+    pub pdb_id: String,
+    pub title: String,
+    #[serde(rename = "organism_scientific_name")]
+    pub organisms: Vec<String>,
+    /// In Å. Absent for e.g. NMR structures.
+    pub resolution: Option<f32>,
+    pub experimental_method: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchResults {
+    /// The total number of matching documents; not just the ones in this page.
+    pub num_found: u32,
+    pub docs: Vec<SearchDoc>,
+}
+
+#[derive(Deserialize)]
+struct SolrResponse {
+    #[serde(rename = "numFound")]
+    num_found: u32,
+    #[serde(default)]
+    docs: Vec<SearchDoc>,
+}
+
+#[derive(Deserialize)]
+struct SolrResp {
+    response: SolrResponse,
+}
+
+/// Search PDBe structures, using its Solr-based search. `query` uses Solr syntax, e.g. "kinase", or
+/// "molecule_name:hemoglobin". An alternative to `rcsb::search`. `rows` and `start` are for paging.
+pub fn search(
+    query: &str,
+    filters: &[SearchFilter],
+    rows: u32,
+    start: u32,
+) -> Result<SearchResults, ReqError> {
+    let mut url = Url::parse(SEARCH_URL).unwrap();
+    {
+        let mut pairs = url.query_pairs_mut();
+        pairs
+            .append_pair("q", query)
+            .append_pair("wt", "json")
+            .append_pair("rows", &rows.to_string())
+            .append_pair("start", &start.to_string())
+            .append_pair(
+                "fl",
+                "pdb_id,title,organism_scientific_name,resolution,experimental_method",
+            );
+        for filter in filters {
+            pairs.append_pair("fq", &filter.to_fq());
+        }
+    }
+
+    let agent = make_agent();
+    let mut resp = agent.get(url.as_str()).call()?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    let parsed: SolrResp = serde_json::from_str(&resp.body_mut().read_to_string()?)?;
+
+    Ok(SearchResults {
+        num_found: parsed.response.num_found,
+        docs: parsed.response.docs,
+    })
+}
+
+/// A kind of geometry or fit problem flagged by wwPDB validation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum OutlierType {
+    #[serde(rename = "clashes")]
+    Clash,
+    #[serde(rename = "symm_clashes")]
+    SymmetryClash,
+    #[serde(rename = "ramachandran_outliers")]
+    Ramachandran,
+    #[serde(rename = "sidechain_outliers")]
+    Sidechain,
+    /// Poor fit to the electron density; real-space R-value Z-score above 2.
+    #[serde(rename = "RSRZ")]
+    Rsrz,
+    #[serde(rename = "bond_lengths")]
+    BondLength,
+    #[serde(rename = "bond_angles")]
+    BondAngle,
+    #[serde(rename = "planes")]
+    Plane,
+    #[serde(rename = "chirals")]
+    Chiral,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ValidationResidue {
+    /// Sequential (mmCIF label) residue number.
+    pub residue_number: i32,
+    pub author_residue_number: Option<i32>,
+    pub author_insertion_code: Option<String>,
+    /// The alternate location, if the outlier is specific to one.
+    pub alt_code: Option<String>,
+    pub outlier_types: Vec<OutlierType>,
+}
+
+impl ValidationResidue {
+    pub fn has(&self, outlier: OutlierType) -> bool {
+        self.outlier_types.contains(&outlier)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ValidationModel {
+    pub model_id: u32,
+    /// Only residues with at least one outlier are included.
+    pub residues: Vec<ValidationResidue>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ValidationChain {
+    /// Author chain ID.
+    pub chain_id: String,
+    /// Label (mmCIF) chain ID.
+    pub struct_asym_id: String,
+    pub models: Vec<ValidationModel>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ValidationEntity {
+    pub entity_id: u32,
+    pub chains: Vec<ValidationChain>,
+}
+
+#[derive(Deserialize)]
+struct ValidationResp {
+    #[serde(default)]
+    molecules: Vec<ValidationEntity>,
+}
+
+/// Load per-residue validation outliers of an entry, e.g. Ramachandran outliers, clashes, and poor
+/// density fit (RSRZ). Useful for coloring a structure by local quality.
+pub fn get_validation_residuewise(pdb_id: &str) -> Result<Vec<ValidationEntity>, ReqError> {
+    let resp: ValidationResp = get_entry(
+        &format!("{VALIDATION_URL}/residuewise_outlier_summary/entry"),
+        pdb_id,
+    )?;
+
+    Ok(resp.molecules)
+}
+
 pub fn open_overview(id: &str) {
     if let Err(e) = webbrowser::open(&format!("{BASE_URL}/{id}")) {
         eprintln!("Failed to open the web browser: {:?}", e);
@@ -120,20 +714,143 @@ pub fn open_overview(id: &str) {
 //     Ok(parsed.structure.structures)
 // }
 
-/// Note: This loads the "ideal" SDF; not the "model" one.
-fn sdf_url(ident: &str) -> String {
+/// Download a file from the PDBe entry files, e.g. "download/1cbs_updated.cif", returning its bytes.
+fn load_entry_file(path: &str) -> Result<Vec<u8>, ReqError> {
+    let agent = make_agent_download();
+
+    let resp = agent.get(&format!("{ENTRY_FILES_URL}/{path}")).call()?;
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound {
+            ident: path.to_owned(),
+        });
+    }
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    // These may be large; don't use the size-limited reader.
+    let mut result = Vec::new();
+    resp.into_body().into_reader().read_to_end(&mut result)?;
+
+    Ok(result)
+}
+
+/// Download the PDBe "updated" mmCIF file of an entry, returning a CIF string. This includes SIFTS
+/// annotations, and standardized data. An alternative to `rcsb::load_cif`; `rcsb::load_cif_with_fallback`
+/// uses this if the RCSB is unavailable.
+pub fn load_updated_cif(pdb_id: &str) -> Result<String, ReqError> {
+    let data = load_entry_file(&format!(
+        "download/{}_updated.cif",
+        pdb_id.trim().to_lowercase()
+    ))?;
+
+    Ok(String::from_utf8_lossy(&data).into_owned())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapKind {
+    /// The 2Fo-Fc map; the electron density model.
+    TwoFoFc,
+    /// The Fo-Fc difference map.
+    FoFc,
+}
+
+/// Download a precomputed electron density map from PDBe, returning the raw CCP4 file bytes. Unlike
+/// the RCSB, which usually only has map coefficients, PDBe has these for most X-ray entries.
+pub fn load_density_ccp4(pdb_id: &str, kind: MapKind) -> Result<Vec<u8>, ReqError> {
+    let id = pdb_id.trim().to_lowercase();
+
+    let file_name = match kind {
+        MapKind::TwoFoFc => format!("{id}.ccp4"),
+        MapKind::FoFc => format!("{id}_diff.ccp4"),
+    };
+
+    load_entry_file(&file_name)
+}
+
+/// Download the sequences of an entry's polymer entities from PDBe, returning FASTA text. A second
+/// source for sequences, in addition to the RCSB.
+pub fn load_fasta(pdb_id: &str) -> Result<String, ReqError> {
+    let agent = make_agent();
+    let id = pdb_id.trim().to_lowercase();
+
+    let mut resp = agent.get(&format!("{FASTA_URL}/{id}/fasta")).call()?;
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound { ident: id });
+    }
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    Ok(resp.body_mut().read_to_string()?)
+}
+
+/// The sequence of one polymer entity, and the chains it's present in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainSequence {
+    /// Author chain IDs, e.g. `["A", "B"]`.
+    pub chains: Vec<String>,
+    /// The header text after the identifier, if present. E.g. the molecule name.
+    pub description: String,
+    pub seq: String,
+}
+
+/// Load the sequences of an entry from PDBe, parsed per entity. Headers are of the form
+/// ">pdb|1cbs|A,B Description".
+pub fn load_fasta_chains(pdb_id: &str) -> Result<Vec<ChainSequence>, ReqError> {
+    let text = load_fasta(pdb_id)?;
+
+    Ok(parse_fasta(&text)
+        .into_iter()
+        .map(|rec| {
+            let (ident, description) = rec.header.split_once(' ').unwrap_or((&rec.header, ""));
+
+            let chains = ident
+                .rsplit('|')
+                .next()
+                .unwrap_or_default()
+                .split(',')
+                .map(|c| c.trim().to_owned())
+                .filter(|c| !c.is_empty())
+                .collect();
+
+            ChainSequence {
+                chains,
+                description: description.trim().to_owned(),
+                seq: rec.seq,
+            }
+        })
+        .collect())
+}
+
+/// Which set of coordinates to use for a chemical component's SDF.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SdfCoords {
+    /// Idealized geometry, e.g. from energy minimization.
+    #[default]
+    Ideal,
+    /// Geometry as observed in an experimental structure.
+    Model,
+}
+
+fn sdf_url(ident: &str, coords: SdfCoords) -> String {
+    let suffix = match coords {
+        SdfCoords::Ideal => "ideal",
+        SdfCoords::Model => "model",
+    };
+
     format!(
-        "https://www.ebi.ac.uk/pdbe/static/files/pdbechem_v2/{}_ideal.sdf",
+        "https://www.ebi.ac.uk/pdbe/static/files/pdbechem_v2/{}_{suffix}.sdf",
         ident.to_uppercase()
     )
 }
 
 /// Download an SDF file from PDBe, returning a SDF string.
-pub fn load_sdf(ident: &str) -> Result<String, ReqError> {
+pub fn load_sdf(ident: &str, coords: SdfCoords) -> Result<String, ReqError> {
     let agent = make_agent();
 
     Ok(agent
-        .get(sdf_url(ident))
+        .get(sdf_url(ident, coords))
         .call()?
         .body_mut()
         .read_to_string()?)
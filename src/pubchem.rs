@@ -3,19 +3,61 @@
 //!
 //! This includes specific lookups, and an interface to the general URL-based API.
 
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    io, thread,
+    time::{Duration, Instant},
+};
 
 use serde::Deserialize;
+use serde_aux::prelude::deserialize_option_number_from_string;
+use url::Url;
 
-use crate::{ReqError, make_agent};
+use ureq::Agent;
+
+use crate::{HTTP_TIMEOUT, HTTP_TIMEOUT_CONNECT, ReqError, cactus};
+
+pub mod assay;
+pub mod cell;
+pub mod gene;
+pub mod pathway;
+pub mod protein;
+pub mod pug_view;
+pub mod taxonomy;
+pub mod throttle;
 
 const BASE_COMPOUND_URL: &str = "https://pubchem.ncbi.nlm.nih.gov/compound";
 
 const BASE_PUG_URL: &str = "https://pubchem.ncbi.nlm.nih.gov/rest/pug";
 
+const AUTOCOMPLETE_URL: &str = "https://pubchem.ncbi.nlm.nih.gov/rest/autocomplete/compound";
+
 const PROTEIN_LOOKUP_URL: &str =
     "https://pubchem.ncbi.nlm.nih.gov/rest/pug_view/structure/compound";
 
+/// An agent for PubChem requests. These are throttled according to the service's load; see
+/// [`throttle`].
+pub(crate) fn make_agent_pubchem() -> Agent {
+    let config = Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(HTTP_TIMEOUT)))
+        .http_status_as_error(false)
+        .middleware(throttle::middleware)
+        .build();
+
+    config.into()
+}
+
+/// A throttled agent for large responses, without the global timeout.
+fn make_agent_pubchem_download() -> Agent {
+    let config = Agent::config_builder()
+        .timeout_connect(Some(Duration::from_secs(HTTP_TIMEOUT_CONNECT)))
+        .http_status_as_error(false)
+        .middleware(throttle::middleware)
+        .build();
+
+    config.into()
+}
+
 #[allow(unused)]
 #[derive(Clone, Debug, Deserialize)]
 pub struct Taxonomy {
@@ -145,6 +187,73 @@ impl Display for StructureSearchNamespace {
     }
 }
 
+/// Cross-reference types, e.g. registry IDs from other databases. The final four are only valid
+/// as output; not as an input namespace.
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Input
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum XrefType {
+    /// The depositor's identifier.
+    RegistryId,
+    /// Registry number, e.g. CAS.
+    Rn,
+    PubMedId,
+    MmdbId,
+    ProteinGi,
+    NucleotideGi,
+    TaxonomyId,
+    MimId,
+    GeneId,
+    ProbeId,
+    PatentId,
+    SourceName,
+    SourceCategory,
+    /// The depositor's database URL.
+    DbUrl,
+    /// The depositor's URL for this record.
+    SbUrl,
+}
+
+impl Display for XrefType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::RegistryId => "RegistryID",
+            Self::Rn => "RN",
+            Self::PubMedId => "PubMedID",
+            Self::MmdbId => "MMDBID",
+            Self::ProteinGi => "ProteinGI",
+            Self::NucleotideGi => "NucleotideGI",
+            Self::TaxonomyId => "TaxonomyID",
+            Self::MimId => "MIMID",
+            Self::GeneId => "GeneID",
+            Self::ProbeId => "ProbeID",
+            Self::PatentId => "PatentID",
+            Self::SourceName => "SourceName",
+            Self::SourceCategory => "SourceCategory",
+            Self::DbUrl => "DBURL",
+            Self::SbUrl => "SBURL",
+        };
+        write!(f, "{v}")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MassKind {
+    MolecularWeight,
+    ExactMass,
+    Monoisotopic,
+}
+
+impl Display for MassKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::MolecularWeight => "molecular_weight",
+            Self::ExactMass => "exact_mass",
+            Self::Monoisotopic => "monoisotopic_mass",
+        };
+        write!(f, "{v}")
+    }
+}
+
 /// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
 #[derive(Clone, PartialEq)]
 pub enum NamespaceCompound {
@@ -156,8 +265,9 @@ pub enum NamespaceCompound {
     Inchikey,
     Formula,
     StructureSearch((StructureSearchCat, StructureSearchNamespace)),
-    // xrf, // todo
-    // mass // todo
+    Xref(XrefType),
+    /// Use a range or value, e.g. "range/400.0/400.05", or "equals/400.0", as the identifier.
+    Mass(MassKind),
     ListKey,
     FastSearch((FastSearchCat, StructureSearchNamespace)),
 }
@@ -174,6 +284,8 @@ impl Display for NamespaceCompound {
             Self::StructureSearch((search_cat, search_namespace)) => {
                 &format!("{search_cat}/{search_namespace}")
             }
+            Self::Xref(xref) => &format!("xref/{xref}"),
+            Self::Mass(kind) => &kind.to_string(),
             Self::ListKey => "listkey",
             Self::FastSearch((search_cat, search_namespace)) => {
                 &format!("{search_cat}/{search_namespace}")
@@ -190,7 +302,7 @@ pub enum NamespaceSubstance {
     SourceId(String),
     SourceAll(String),
     Name,
-    // Xref
+    Xref(XrefType),
     ListKey,
 }
 impl Display for NamespaceSubstance {
@@ -200,7 +312,190 @@ impl Display for NamespaceSubstance {
             Self::SourceId(v) => &format!("sourceid/{v}"),
             Self::SourceAll(v) => &format!("sourceall/{v}"),
             Self::Name => "name",
+            Self::Xref(xref) => &format!("xref/{xref}"),
+            Self::ListKey => "listkey",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Input
+#[derive(Clone, Copy, PartialEq)]
+pub enum AssayType {
+    All,
+    Confirmatory,
+    DoseResponse,
+    OnHold,
+    Panel,
+    Rnai,
+    Screening,
+    Summary,
+    CellBased,
+    Biochemical,
+    InVivo,
+    InVitro,
+    ActiveConcentrationSpecified,
+}
+
+impl Display for AssayType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::All => "all",
+            Self::Confirmatory => "confirmatory",
+            Self::DoseResponse => "doseresponse",
+            Self::OnHold => "onhold",
+            Self::Panel => "panel",
+            Self::Rnai => "rnai",
+            Self::Screening => "screening",
+            Self::Summary => "summary",
+            Self::CellBased => "cellbased",
+            Self::Biochemical => "biochemical",
+            Self::InVivo => "invivo",
+            Self::InVitro => "invitro",
+            Self::ActiveConcentrationSpecified => "activeconcentrationspecified",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// Identifier types for assay targets, used both as input, and as output of the targets
+/// operation.
+#[derive(Clone, Copy, PartialEq)]
+pub enum AssayTargetType {
+    Gi,
+    ProteinName,
+    GeneId,
+    GeneSymbol,
+    Accession,
+}
+
+impl Display for AssayTargetType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Gi => "gi",
+            Self::ProteinName => "proteinname",
+            Self::GeneId => "geneid",
+            Self::GeneSymbol => "genesymbol",
+            Self::Accession => "accession",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, PartialEq)]
+pub enum NamespaceAssay {
+    Aid,
+    ListKey,
+    Type(AssayType),
+    SourceAll(String),
+    Target(AssayTargetType),
+    /// The name of an activity column, e.g. "ec50".
+    Activity(String),
+}
+
+impl Display for NamespaceAssay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Aid => "aid",
             Self::ListKey => "listkey",
+            Self::Type(v) => &format!("type/{v}"),
+            Self::SourceAll(v) => &format!("sourceall/{v}"),
+            Self::Target(v) => &format!("target/{v}"),
+            Self::Activity(v) => &format!("activity/{v}"),
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum NamespaceGene {
+    GeneId,
+    /// Human genes, by default.
+    GeneSymbol,
+    Synonym,
+}
+
+impl Display for NamespaceGene {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::GeneId => "geneid",
+            Self::GeneSymbol => "genesymbol",
+            Self::Synonym => "synonym",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum NamespaceProtein {
+    /// E.g. a UniProt accession, "P00533".
+    Accession,
+    Gi,
+    Synonym,
+}
+
+impl Display for NamespaceProtein {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Accession => "accession",
+            Self::Gi => "gi",
+            Self::Synonym => "synonym",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum NamespaceTaxonomy {
+    TaxId,
+    /// A scientific or common name, e.g. "Homo sapiens", or "human".
+    Synonym,
+}
+
+impl Display for NamespaceTaxonomy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::TaxId => "taxid",
+            Self::Synonym => "synonym",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum NamespacePathway {
+    /// A pathway accession, as source and ID, e.g. "Reactome:R-HSA-70171".
+    PwAcc,
+}
+
+impl Display for NamespacePathway {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::PwAcc => "pwacc",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum NamespaceCell {
+    /// A Cellosaurus accession, e.g. "CVCL_0030".
+    CellAcc,
+    /// A name, e.g. "HeLa".
+    Synonym,
+}
+
+impl Display for NamespaceCell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::CellAcc => "cellacc",
+            Self::Synonym => "synonym",
         };
         write!(f, "{v}")
     }
@@ -211,6 +506,12 @@ impl Display for NamespaceSubstance {
 pub enum Namespace {
     Compound(NamespaceCompound),
     Substance(NamespaceSubstance),
+    Assay(NamespaceAssay),
+    Gene(NamespaceGene),
+    Protein(NamespaceProtein),
+    Taxonomy(NamespaceTaxonomy),
+    Pathway(NamespacePathway),
+    Cell(NamespaceCell),
 }
 
 impl Display for Namespace {
@@ -218,6 +519,12 @@ impl Display for Namespace {
         let v = match self {
             Self::Compound(v) => v.to_string(),
             Self::Substance(v) => v.to_string(),
+            Self::Assay(v) => v.to_string(),
+            Self::Gene(v) => v.to_string(),
+            Self::Protein(v) => v.to_string(),
+            Self::Taxonomy(v) => v.to_string(),
+            Self::Pathway(v) => v.to_string(),
+            Self::Cell(v) => v.to_string(),
         };
         write!(f, "{v}")
     }
@@ -234,7 +541,7 @@ pub enum OpSpecCompound {
     Aids,
     AssaySummary,
     Classification,
-    Xrefs,
+    Xrefs(Vec<XrefType>),
     Description,
     Conformers,
 }
@@ -250,7 +557,13 @@ impl Display for OpSpecCompound {
             Self::Aids => "aids",
             Self::AssaySummary => "assaysummary",
             Self::Classification => "classification",
-            Self::Xrefs => "xrefs",
+            Self::Xrefs(v) => &format!(
+                "xrefs/{}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
             Self::Description => "description",
             Self::Conformers => "conformers",
         };
@@ -259,7 +572,7 @@ impl Display for OpSpecCompound {
 }
 
 /// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub enum OpSpecSubstance {
     Record,
     Synonyms,
@@ -268,7 +581,7 @@ pub enum OpSpecSubstance {
     Aids,
     AssaySummary,
     Classification,
-    Xrefs,
+    Xrefs(Vec<XrefType>),
     Description,
 }
 
@@ -282,8 +595,145 @@ impl Display for OpSpecSubstance {
             Self::Aids => "aids",
             Self::AssaySummary => "assaysummary",
             Self::Classification => "classification",
-            Self::Xrefs => "xrefs",
+            Self::Xrefs(v) => &format!(
+                "xrefs/{}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            Self::Description => "description",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, PartialEq)]
+pub enum OpSpecAssay {
+    Record,
+    Concise,
+    Aids,
+    Sids,
+    Cids,
+    Description,
+    Targets(Vec<AssayTargetType>),
+    Summary,
+    Classification,
+    /// Dose-response curve data, by SID.
+    DoseResponse,
+}
+
+impl Display for OpSpecAssay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Record => "record",
+            Self::Concise => "concise",
+            Self::Aids => "aids",
+            Self::Sids => "sids",
+            Self::Cids => "cids",
             Self::Description => "description",
+            Self::Targets(v) => {
+                let v: Vec<_> = v.iter().map(|t| t.to_string()).collect();
+                &format!("targets/{}", v.join(","))
+            }
+            Self::Summary => "summary",
+            Self::Classification => "classification",
+            Self::DoseResponse => "doseresponse/sid",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpSpecGene {
+    Summary,
+    Aids,
+    Concise,
+    /// Pathway accessions.
+    PwAccs,
+}
+
+impl Display for OpSpecGene {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Summary => "summary",
+            Self::Aids => "aids",
+            Self::Concise => "concise",
+            Self::PwAccs => "pwaccs",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpSpecProtein {
+    Summary,
+    Aids,
+    Concise,
+}
+
+impl Display for OpSpecProtein {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Summary => "summary",
+            Self::Aids => "aids",
+            Self::Concise => "concise",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpSpecTaxonomy {
+    Summary,
+    Aids,
+}
+
+impl Display for OpSpecTaxonomy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Summary => "summary",
+            Self::Aids => "aids",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpSpecPathway {
+    Summary,
+    Cids,
+    Genes,
+}
+
+impl Display for OpSpecPathway {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Summary => "summary",
+            Self::Cids => "cids",
+            Self::Genes => "geneids",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=The-URL-Path
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpSpecCell {
+    Summary,
+    Aids,
+}
+
+impl Display for OpSpecCell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Summary => "summary",
+            Self::Aids => "aids",
         };
         write!(f, "{v}")
     }
@@ -294,6 +744,12 @@ impl Display for OpSpecSubstance {
 pub enum OperationSpecification {
     Substance(OpSpecSubstance),
     Compound(OpSpecCompound),
+    Assay(OpSpecAssay),
+    Gene(OpSpecGene),
+    Protein(OpSpecProtein),
+    Taxonomy(OpSpecTaxonomy),
+    Pathway(OpSpecPathway),
+    Cell(OpSpecCell),
 }
 
 impl Display for OperationSpecification {
@@ -301,11 +757,98 @@ impl Display for OperationSpecification {
         let v = match self {
             Self::Substance(v) => v.to_string(),
             Self::Compound(v) => v.to_string(),
+            Self::Assay(v) => v.to_string(),
+            Self::Gene(v) => v.to_string(),
+            Self::Protein(v) => v.to_string(),
+            Self::Taxonomy(v) => v.to_string(),
+            Self::Pathway(v) => v.to_string(),
+            Self::Cell(v) => v.to_string(),
         };
         write!(f, "{v}")
     }
 }
 
+/// A tabular result, e.g. from the concise or dose-response operations. All cells are converted
+/// to strings.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// The index of a column, by name. E.g. "CID", or "Activity Outcome".
+    pub fn col_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+
+    /// All values of a column, by name.
+    pub fn col(&self, name: &str) -> Option<Vec<&str>> {
+        let i = self.col_index(name)?;
+        Some(
+            self.rows
+                .iter()
+                .map(|r| r.get(i).map(|v| v.as_str()).unwrap_or_default())
+                .collect(),
+        )
+    }
+
+    /// Parse from the JSON `Table` format returned by PUG-REST.
+    pub fn from_json(json: &str) -> Result<Self, ReqError> {
+        let parsed: TableResp = serde_json::from_str(json)?;
+
+        let rows = parsed
+            .table
+            .row
+            .into_iter()
+            .map(|r| {
+                r.cell
+                    .into_iter()
+                    .map(|c| match c {
+                        serde_json::Value::String(v) => v,
+                        v => v.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            columns: parsed.table.columns.column,
+            rows,
+        })
+    }
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TableColumns {
+    #[serde(rename = "Column")]
+    column: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TableRow {
+    #[serde(rename = "Cell")]
+    cell: Vec<serde_json::Value>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TableInner {
+    #[serde(rename = "Columns")]
+    columns: TableColumns,
+    #[serde(rename = "Row", default)]
+    row: Vec<TableRow>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TableResp {
+    #[serde(rename = "Table")]
+    table: TableInner,
+}
+
 /// Calls the flexible [URL-based API](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=URL-based-API).
 /// Makes GET requests by combining parameters. Returns JSON results.
 ///
@@ -316,37 +859,542 @@ pub fn url_api_query(
     namespace: Namespace,
     identifiers: &[String],
     op_spec: OperationSpecification,
-    // op_options, Vec<Operation> // todo
     // todo: String output for now.
 ) -> Result<String, ReqError> {
-    // todo: Op options
-    let idents = identifiers.join(","); // todo: QC the joiner.
-    let url = format!("{BASE_PUG_URL}/{domain}/{namespace}/{idents}/{op_spec}/JSON");
-
-    let agent = make_agent();
+    url_api_query_with_options(
+        domain,
+        namespace,
+        identifiers,
+        op_spec,
+        &OpOptions::default(),
+    )
+}
 
-    Ok(agent.get(url).call()?.body_mut().read_to_string()?)
+/// Operation options, passed as URL query parameters.
+/// [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Operation-Options)
+#[derive(Clone, Debug, Default)]
+pub struct OpOptions {
+    /// Return a ListKey instead of the identifiers; retrieve them with the `ListKey` namespace.
+    /// This is how large identifier lists are paginated.
+    pub list_return_listkey: bool,
+    /// For the `ListKey` namespace: The 0-based index of the first identifier to return.
+    pub listkey_start: Option<u32>,
+    /// For the `ListKey` namespace: The number of identifiers to return.
+    pub listkey_count: Option<u32>,
+    /// Other options, e.g. `("Threshold", "95")`.
+    pub other: Vec<(String, String)>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
-struct SimilarMolsCidResp {
-    #[serde(rename = "CID")]
-    pub cid: Vec<u32>,
+impl OpOptions {
+    /// Options to retrieve one page of a ListKey's identifiers.
+    pub fn page(start: u32, count: u32) -> Self {
+        Self {
+            listkey_start: Some(start),
+            listkey_count: Some(count),
+            ..Default::default()
+        }
+    }
+
+    /// Add these options to a URL's query string, encoded.
+    fn append_to(&self, url: &mut Url) {
+        let mut pairs = Vec::new();
+        if self.list_return_listkey {
+            pairs.push(("list_return".to_owned(), "listkey".to_owned()));
+        }
+        if let Some(v) = self.listkey_start {
+            pairs.push(("listkey_start".to_owned(), v.to_string()));
+        }
+        if let Some(v) = self.listkey_count {
+            pairs.push(("listkey_count".to_owned(), v.to_string()));
+        }
+        pairs.extend(self.other.iter().cloned());
+
+        if !pairs.is_empty() {
+            url.query_pairs_mut().extend_pairs(pairs);
+        }
+    }
 }
 
-#[derive(Clone, Debug, Deserialize)]
-/// For decoding
-struct SimilarMolsResp {
-    #[serde(rename = "IdentifierList")]
-    pub identifier_list: SimilarMolsCidResp,
+/// Build a PUG REST URL from path parts. Parts may contain several segments, e.g.
+/// "fastsimilarity_3d/cid", which are split; `idents` is a single segment, and is encoded, so
+/// it may contain spaces etc.
+fn pug_url(before: &[String], idents: Option<&str>, after: &[String], options: &OpOptions) -> Url {
+    let mut url = Url::parse(BASE_PUG_URL).unwrap();
+    {
+        let mut segments = url.path_segments_mut().unwrap();
+        let split = |parts: &[String]| -> Vec<String> {
+            parts
+                .iter()
+                .flat_map(|p| p.split('/').map(str::to_owned).collect::<Vec<_>>())
+                .filter(|p| !p.is_empty())
+                .collect()
+        };
+
+        segments.extend(split(before));
+        if let Some(idents) = idents {
+            segments.push(idents);
+        }
+        segments.extend(split(after));
+    }
+    options.append_to(&mut url);
+
+    url
 }
 
-/// Find similar molecules using the fast 3D lookup.
-// todo: Expose in bio_files or here your Ident enum, and pass that here instead of requiring CID
-// todo: You will eventually need to do this using SMILES, for compatibility with custom molecules.
-// pub fn find_similar_mols(cid: u32) -> Result<Vec<String>, ReqError> {
+/// The URL for a URL-based API query. `idents` are comma-separated.
+fn api_url(
+    domain: Domain,
+    namespace: Namespace,
+    idents: &str,
+    op_spec: OperationSpecification,
+    options: &OpOptions,
+    format: OutputFormat,
+) -> Url {
+    pug_url(
+        &[domain.to_string(), namespace.to_string()],
+        Some(idents),
+        &[op_spec.to_string(), format.to_string()],
+        options,
+    )
+}
+
+/// Like [`url_api_query`], but with operation options, e.g. for pagination. To page through a
+/// large result set: Make the query with `list_return_listkey` set, then query the `ListKey`
+/// namespace with the returned key, using [`OpOptions::page`]. [`ListKeyJob`] does this for
+/// CID results.
+pub fn url_api_query_with_options(
+    domain: Domain,
+    namespace: Namespace,
+    identifiers: &[String],
+    op_spec: OperationSpecification,
+    options: &OpOptions,
+) -> Result<String, ReqError> {
+    let bytes = url_api_query_format(
+        domain,
+        namespace,
+        identifiers,
+        op_spec,
+        options,
+        OutputFormat::Json,
+    )?;
+
+    String::from_utf8(bytes).map_err(|_| ReqError::Deserialize)
+}
+
+/// https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Output
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Xml,
+    Sdf,
+    Csv,
+    Txt,
+    Png,
+}
+
+impl OutputFormat {
+    pub fn is_binary(self) -> bool {
+        self == Self::Png
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Json => "JSON",
+            Self::Xml => "XML",
+            Self::Sdf => "SDF",
+            Self::Csv => "CSV",
+            Self::Txt => "TXT",
+            Self::Png => "PNG",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// Like [`url_api_query_with_options`], but with a choice of output format. For example, SDF for
+/// records, or CSV for property tables. Returns bytes, as some formats are binary; convert text
+/// formats with `String::from_utf8`. Not all operations support all formats.
+pub fn url_api_query_format(
+    domain: Domain,
+    namespace: Namespace,
+    identifiers: &[String],
+    op_spec: OperationSpecification,
+    options: &OpOptions,
+    format: OutputFormat,
+) -> Result<Vec<u8>, ReqError> {
+    let idents = identifiers.join(",");
+    let url = api_url(domain, namespace, &idents, op_spec, options, format);
+
+    let agent = make_agent_pubchem();
+
+    let mut resp = agent.get(url.as_str()).call()?;
+    // Errors are returned as a `Fault` body in the requested format; don't pass these on as
+    // data. Asynchronous operations return 202 while a ListKey is pending.
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound { ident: idents });
+    }
+    if resp.status() != 200 && resp.status() != 202 {
+        return Err(ReqError::Http);
+    }
+
+    Ok(resp.body_mut().read_to_vec()?)
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ListKeyWaiting {
+    #[serde(rename = "ListKey")]
+    list_key: String,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ListKeyIdentifierList {
+    #[serde(rename = "CID", default)]
+    cid: Vec<u32>,
+    #[serde(rename = "ListKey")]
+    list_key: Option<String>,
+    #[serde(rename = "Size")]
+    size: Option<u32>,
+}
+
+/// Deserializing only. Asynchronous operations return `Waiting` until complete.
+#[allow(unused)]
+#[derive(Debug, Deserialize)]
+enum ListKeyResp {
+    Waiting(ListKeyWaiting),
+    IdentifierList(ListKeyIdentifierList),
+    Fault(serde_json::Value),
+}
+
+// Backoff when polling for results: Start at the minimum, doubling each poll.
+const LIST_KEY_POLL_MIN: Duration = Duration::from_secs(1);
+const LIST_KEY_POLL_MAX: Duration = Duration::from_secs(10);
+
+/// An asynchronous compound search, e.g. substructure or superstructure. PubChem runs these
+/// in the background, and returns a ListKey that we poll until results are ready. Results are
+/// then retrieved in pages.
+///
+/// Example: `ListKeyJob::submit(NamespaceCompound::StructureSearch((Substructure, Smiles)), "c1ccccc1")?`,
+/// then `.wait(Duration::from_secs(120))?`, then `.cids_all(10_000)?`.
+#[derive(Clone, Debug)]
+pub struct ListKeyJob {
+    pub list_key: String,
+    /// The number of results. Set once the job is complete.
+    pub size: Option<u32>,
+}
+
+impl ListKeyJob {
+    /// Submit a search. `query` is e.g. a SMILES string, or CID, depending on the namespace.
+    pub fn submit(namespace: NamespaceCompound, query: &str) -> Result<Self, ReqError> {
+        Self::submit_with_options(namespace, query, &[])
+    }
+
+    /// Submit a search, with additional operation options, e.g. `("MatchCharges", "true")`.
+    /// SDF and Molfile inputs are sent in the request body, so a full block can be used as the
+    /// query, e.g. for a custom-drawn molecule without a clean SMILES representation.
+    pub fn submit_with_options(
+        namespace: NamespaceCompound,
+        query: &str,
+        options: &[(&str, &str)],
+    ) -> Result<Self, ReqError> {
+        let sdf_input = matches!(
+            namespace,
+            NamespaceCompound::StructureSearch((_, StructureSearchNamespace::Sdf))
+                | NamespaceCompound::FastSearch((_, StructureSearchNamespace::Sdf))
+        );
+
+        let mut url = Url::parse(&format!("{BASE_PUG_URL}/compound/{namespace}")).unwrap();
+        {
+            let mut segments = url.path_segments_mut().unwrap();
+            if !sdf_input {
+                segments.push(query);
+            }
+            segments.extend(["cids", "JSON"]);
+        }
+        {
+            let mut pairs = url.query_pairs_mut();
+            pairs.append_pair("list_return", "listkey");
+            for (k, v) in options {
+                pairs.append_pair(k, v);
+            }
+        }
+
+        let agent = make_agent_pubchem();
+        let resp = if sdf_input {
+            agent
+                .post(url.as_str())
+                .send_form([("sdf", query)])?
+                .body_mut()
+                .read_to_string()?
+        } else {
+            agent
+                .get(url.as_str())
+                .call()?
+                .body_mut()
+                .read_to_string()?
+        };
+
+        match serde_json::from_str(&resp)? {
+            ListKeyResp::Waiting(w) => Ok(Self {
+                list_key: w.list_key,
+                size: None,
+            }),
+            ListKeyResp::IdentifierList(l) => Ok(Self {
+                list_key: l.list_key.ok_or(ReqError::Deserialize)?,
+                size: l.size,
+            }),
+            ListKeyResp::Fault(_) => Err(ReqError::Http),
+        }
+    }
+
+    /// Fetch a page of results. Returns `None` if the job is still running.
+    fn fetch_page(
+        &self,
+        start: u32,
+        count: u32,
+    ) -> Result<Option<ListKeyIdentifierList>, ReqError> {
+        let resp = url_api_query_with_options(
+            Domain::Compound,
+            Namespace::Compound(NamespaceCompound::ListKey),
+            std::slice::from_ref(&self.list_key),
+            OperationSpecification::Compound(OpSpecCompound::Cids),
+            &OpOptions::page(start, count),
+        )?;
+
+        match serde_json::from_str(&resp)? {
+            ListKeyResp::Waiting(_) => Ok(None),
+            ListKeyResp::IdentifierList(l) => Ok(Some(l)),
+            ListKeyResp::Fault(_) => Err(ReqError::Http),
+        }
+    }
+
+    /// Returns true if the job is complete, and sets `size` when it is.
+    pub fn poll(&mut self) -> Result<bool, ReqError> {
+        match self.fetch_page(0, 1)? {
+            Some(l) => {
+                if l.size.is_some() {
+                    self.size = l.size;
+                }
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Block until the job is complete, polling with exponential backoff. Returns an error of
+    /// kind `TimedOut` if it's not complete within `timeout`.
+    pub fn wait(&mut self, timeout: Duration) -> Result<(), ReqError> {
+        let start = Instant::now();
+        let mut delay = LIST_KEY_POLL_MIN;
+
+        loop {
+            if self.poll()? {
+                return Ok(());
+            }
+
+            if start.elapsed() + delay > timeout {
+                return Err(ReqError::Io(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "PubChem ListKey job not complete",
+                )));
+            }
+
+            thread::sleep(delay);
+            delay = (delay * 2).min(LIST_KEY_POLL_MAX);
+        }
+    }
+
+    /// Retrieve a page of CIDs. `start` is 0-based. Call once the job is complete; returns
+    /// `ReqError::Pending` if it isn't.
+    pub fn cids_page(&self, start: u32, count: u32) -> Result<Vec<u32>, ReqError> {
+        Ok(self.fetch_page(start, count)?.ok_or(ReqError::Pending)?.cid)
+    }
+
+    /// Retrieve all CIDs, one page at a time. Call once the job is complete. A `page_size` of 0 is
+    /// treated as 1. If `size` is known (e.g. after [`Self::wait`]), stops once that many CIDs
+    /// have been retrieved.
+    pub fn cids_all(&self, page_size: u32) -> Result<Vec<u32>, ReqError> {
+        let page_size = page_size.max(1);
+
+        let mut result = Vec::new();
+        loop {
+            let fetched = result.len() as u32;
+            let count = match self.size {
+                Some(size) if fetched >= size => break,
+                Some(size) => page_size.min(size - fetched),
+                None => page_size,
+            };
+
+            let page = self.cids_page(fetched, count)?;
+            let page_len = page.len();
+            result.extend(page);
+
+            if page_len < count as usize {
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// How to match stereochemistry in structure searches.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum StereoMatch {
+    #[default]
+    Ignore,
+    Exact,
+    Relative,
+    NonConflicting,
+}
+
+impl Display for StereoMatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Ignore => "ignore",
+            Self::Exact => "exact",
+            Self::Relative => "relative",
+            Self::NonConflicting => "nonconflicting",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// Options for substructure and superstructure searches. Defaults match PubChem's.
+/// [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Structure-Search-Operations)
+#[derive(Clone, Debug)]
+pub struct StructureSearchOptions {
+    pub match_isotopes: bool,
+    pub match_charges: bool,
+    pub match_tautomers: bool,
+    /// Don't allow matches where the query's rings are embedded in larger rings.
+    pub rings_not_embedded: bool,
+    pub single_double_bonds_match: bool,
+    pub chains_match_rings: bool,
+    pub strip_hydrogen: bool,
+    pub stereo: StereoMatch,
+    pub max_records: Option<u32>,
+}
+
+impl Default for StructureSearchOptions {
+    fn default() -> Self {
+        Self {
+            match_isotopes: false,
+            match_charges: false,
+            match_tautomers: false,
+            rings_not_embedded: false,
+            single_double_bonds_match: true,
+            chains_match_rings: true,
+            strip_hydrogen: false,
+            stereo: StereoMatch::Ignore,
+            max_records: None,
+        }
+    }
+}
+
+impl StructureSearchOptions {
+    fn to_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut result = vec![
+            ("MatchIsotopes", self.match_isotopes.to_string()),
+            ("MatchCharges", self.match_charges.to_string()),
+            ("MatchTautomers", self.match_tautomers.to_string()),
+            ("RingsNotEmbedded", self.rings_not_embedded.to_string()),
+            (
+                "SingleDoubleBondsMatch",
+                self.single_double_bonds_match.to_string(),
+            ),
+            ("ChainsMatchRings", self.chains_match_rings.to_string()),
+            ("StripHydrogen", self.strip_hydrogen.to_string()),
+            ("Stereo", self.stereo.to_string()),
+        ];
+        if let Some(v) = self.max_records {
+            result.push(("MaxRecords", v.to_string()));
+        }
+        result
+    }
+}
+
+/// Submit a substructure or superstructure search, with options. `query` is in the format of
+/// `input`, e.g. SMILES. Returns a job to wait on, and retrieve CIDs from.
+pub fn structure_search(
+    superstructure: bool,
+    input: StructureSearchNamespace,
+    query: &str,
+    options: &StructureSearchOptions,
+) -> Result<ListKeyJob, ReqError> {
+    let cat = if superstructure {
+        StructureSearchCat::Superstructure
+    } else {
+        StructureSearchCat::Substructure
+    };
+
+    let pairs = options.to_pairs();
+    let pairs: Vec<_> = pairs.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+    ListKeyJob::submit_with_options(
+        NamespaceCompound::StructureSearch((cat, input)),
+        query,
+        &pairs,
+    )
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct SimilarMolsCidResp {
+    #[serde(rename = "CID")]
+    pub cid: Vec<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+/// For decoding
+struct SimilarMolsResp {
+    #[serde(rename = "IdentifierList")]
+    pub identifier_list: SimilarMolsCidResp,
+}
+
+/// Find similar molecules using the fast 3D lookup.
+// todo: Expose in bio_files or here your Ident enum, and pass that here instead of requiring CID
+// pub fn find_similar_mols(cid: u32) -> Result<Vec<String>, ReqError> {
 pub fn find_similar_mols(cid: u32) -> Result<Vec<u32>, ReqError> {
-    let resp = url_api_query(
+    find_similar_mols_3d(cid, &Similarity3dOptions::default())
+}
+
+/// Options for 3D similarity searches. Unset fields use the server's defaults.
+#[derive(Clone, Debug, Default)]
+pub struct Similarity3dOptions {
+    /// Minimum shape Tanimoto, 0 - 1. PubChem's default is 0.8.
+    pub shape_tanimoto: Option<f32>,
+    /// Minimum feature (color) Tanimoto, 0 - 1. PubChem's default is 0.5.
+    pub feature_tanimoto: Option<f32>,
+    /// The maximum number of results.
+    pub max_records: Option<u32>,
+}
+
+impl Similarity3dOptions {
+    fn to_op_options(&self) -> OpOptions {
+        let mut other = Vec::new();
+        if let Some(v) = self.shape_tanimoto {
+            other.push(("ShapeTanimoto".to_owned(), v.to_string()));
+        }
+        if let Some(v) = self.feature_tanimoto {
+            other.push(("FeatureTanimoto".to_owned(), v.to_string()));
+        }
+        if let Some(v) = self.max_records {
+            other.push(("MaxRecords".to_owned(), v.to_string()));
+        }
+
+        OpOptions {
+            other,
+            ..Default::default()
+        }
+    }
+}
+
+/// Find similar molecules using the fast 3D lookup, with configurable thresholds.
+pub fn find_similar_mols_3d(cid: u32, options: &Similarity3dOptions) -> Result<Vec<u32>, ReqError> {
+    let resp = url_api_query_with_options(
         Domain::Compound,
         Namespace::Compound(NamespaceCompound::FastSearch((
             FastSearchCat::FastSimilarity3d,
@@ -354,10 +1402,939 @@ pub fn find_similar_mols(cid: u32) -> Result<Vec<u32>, ReqError> {
         ))),
         &[cid.to_string()],
         OperationSpecification::Compound(OpSpecCompound::Cids),
+        &options.to_op_options(),
+    )?;
+
+    let parsed: SimilarMolsResp = serde_json::from_str(&resp)?;
+    Ok(parsed.identifier_list.cid)
+}
+
+/// POST a compound query, with the input (e.g. SMILES, InChI, or SDF) in the request body instead
+/// of the URL path. Use this for inputs that are long, or contain characters that break URL paths.
+/// `input_name` is the form field, e.g. "smiles".
+fn post_compound_query(
+    namespace: NamespaceCompound,
+    input_name: &str,
+    input: &str,
+    op_spec: OpSpecCompound,
+    options: &OpOptions,
+) -> Result<String, ReqError> {
+    let url = pug_url(
+        &[
+            "compound".to_owned(),
+            namespace.to_string(),
+            op_spec.to_string(),
+            "JSON".to_owned(),
+        ],
+        None,
+        &[],
+        options,
+    );
+
+    let agent = make_agent_pubchem();
+
+    let mut resp = agent.post(url.as_str()).send_form([(input_name, input)])?;
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound {
+            ident: input.to_owned(),
+        });
+    }
+    if resp.status() != 200 && resp.status() != 202 {
+        return Err(ReqError::Http);
+    }
+
+    Ok(resp.body_mut().read_to_string()?)
+}
+
+/// Find molecules similar to one described by a SMILES string, using the fast 2D lookup. This
+/// supports custom molecules that don't have a CID. `threshold` is the minimum Tanimoto similarity,
+/// as a percentage (0 - 100); PubChem's default is 90.
+pub fn find_similar_mols_smiles(smiles: &str, threshold: u8) -> Result<Vec<u32>, ReqError> {
+    let options = OpOptions {
+        other: vec![("Threshold".to_owned(), threshold.min(100).to_string())],
+        ..Default::default()
+    };
+
+    // We POST the SMILES, as long ones, and ones with certain characters (e.g. '#', '/')
+    // don't work in the URL path.
+    let resp = post_compound_query(
+        NamespaceCompound::FastSearch((
+            FastSearchCat::FastSimilarity2d,
+            StructureSearchNamespace::Smiles,
+        )),
+        "smiles",
+        smiles,
+        OpSpecCompound::Cids,
+        &options,
+    )?;
+
+    let parsed: SimilarMolsResp = serde_json::from_str(&resp)?;
+    Ok(parsed.identifier_list.cid)
+}
+
+/// Take the first CID from an identifier list response.
+fn first_cid(resp: &str, ident: &str) -> Result<u32, ReqError> {
+    let parsed: SimilarMolsResp = serde_json::from_str(resp)?;
+
+    // PubChem uses CID 0 to indicate no match for some inputs.
+    match parsed.identifier_list.cid.first() {
+        Some(&cid) if cid != 0 => Ok(cid),
+        _ => Err(ReqError::NotFound {
+            ident: ident.to_owned(),
+        }),
+    }
+}
+
+/// Find the CID of a compound from its InChIKey, e.g. "BSYNRYMUTXBXSQ-UHFFFAOYSA-N".
+pub fn cid_from_inchikey(inchikey: &str) -> Result<u32, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Inchikey),
+        &[inchikey.trim().to_owned()],
+        OperationSpecification::Compound(OpSpecCompound::Cids),
+    )?;
+
+    first_cid(&resp, inchikey)
+}
+
+/// Find the CID of a compound from its InChI, e.g. "InChI=1S/C2H6O/c1-2-3/h3H,2H2,1H3".
+pub fn cid_from_inchi(inchi: &str) -> Result<u32, ReqError> {
+    // InChI strings contain '/', so we POST them instead of including them in the URL path.
+    let resp = post_compound_query(
+        NamespaceCompound::Inchi,
+        "inchi",
+        inchi.trim(),
+        OpSpecCompound::Cids,
+        &OpOptions::default(),
+    )?;
+
+    first_cid(&resp, inchi)
+}
+
+/// Find CIDs using a fast structure search (e.g. identity or substructure), with an SDF or Molfile
+/// block as the query. The block is sent in the request body.
+pub fn find_cids_by_sdf(search: FastSearchCat, sdf: &str) -> Result<Vec<u32>, ReqError> {
+    let resp = post_compound_query(
+        NamespaceCompound::FastSearch((search, StructureSearchNamespace::Sdf)),
+        "sdf",
+        sdf,
+        OpSpecCompound::Cids,
+        &OpOptions::default(),
+    )?;
+
+    parse_id_list(&resp, IdKind::Cid)
+}
+
+/// Find CIDs from a cross-reference, e.g. a CAS number: `cids_from_xref(XrefType::Rn, "50-78-2")`.
+pub fn cids_from_xref(xref_type: XrefType, value: &str) -> Result<Vec<u32>, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Xref(xref_type)),
+        &[value.trim().to_owned()],
+        OperationSpecification::Compound(OpSpecCompound::Cids),
+    )?;
+
+    let parsed: SimilarMolsResp = serde_json::from_str(&resp)?;
+    Ok(parsed.identifier_list.cid)
+}
+
+/// Cross-references for a compound. Fields not requested are empty.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Xrefs {
+    #[serde(rename = "CID")]
+    pub cid: u32,
+    #[serde(rename = "RegistryID")]
+    pub registry_ids: Vec<String>,
+    /// Registry numbers, e.g. CAS.
+    #[serde(rename = "RN")]
+    pub rns: Vec<String>,
+    #[serde(rename = "PubMedID")]
+    pub pubmed_ids: Vec<u64>,
+    #[serde(rename = "MMDBID")]
+    pub mmdb_ids: Vec<u64>,
+    #[serde(rename = "ProteinGI")]
+    pub protein_gis: Vec<u64>,
+    #[serde(rename = "NucleotideGI")]
+    pub nucleotide_gis: Vec<u64>,
+    #[serde(rename = "TaxonomyID")]
+    pub taxonomy_ids: Vec<u64>,
+    #[serde(rename = "MIMID")]
+    pub mim_ids: Vec<u64>,
+    #[serde(rename = "GeneID")]
+    pub gene_ids: Vec<u64>,
+    #[serde(rename = "ProbeID")]
+    pub probe_ids: Vec<u64>,
+    #[serde(rename = "PatentID")]
+    pub patent_ids: Vec<String>,
+    #[serde(rename = "SourceName")]
+    pub source_names: Vec<String>,
+    #[serde(rename = "SourceCategory")]
+    pub source_categories: Vec<String>,
+    #[serde(rename = "DBURL")]
+    pub db_urls: Vec<String>,
+    #[serde(rename = "SBURL")]
+    pub sb_urls: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct XrefsInfoList {
+    #[serde(rename = "Information")]
+    information: Vec<Xrefs>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct XrefsResp {
+    #[serde(rename = "InformationList")]
+    information_list: XrefsInfoList,
+}
+
+/// Get cross-references for a compound, e.g. CAS numbers, MMDB IDs, and patent IDs. Only the
+/// types requested are populated.
+pub fn get_xrefs(cid: u32, types: &[XrefType]) -> Result<Xrefs, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Cid),
+        &[cid.to_string()],
+        OperationSpecification::Compound(OpSpecCompound::Xrefs(types.to_vec())),
+    )?;
+
+    let parsed: XrefsResp = serde_json::from_str(&resp)?;
+
+    parsed
+        .information_list
+        .information
+        .into_iter()
+        .next()
+        .ok_or(ReqError::NotFound {
+            ident: cid.to_string(),
+        })
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ClassificationInfo {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Description")]
+    pub description: Vec<String>,
+    #[serde(rename = "URL")]
+    pub url: Option<String>,
+    /// Hierarchy node ID. This is stable across compounds, so can be used for grouping.
+    #[serde(rename = "HNID")]
+    pub hnid: Option<u64>,
+}
+
+/// A node in a classification hierarchy, e.g. a MeSH term, or ChEBI ontology class.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ClassificationNode {
+    /// Unique within its hierarchy; not across hierarchies.
+    #[serde(rename = "NodeID")]
+    pub node_id: String,
+    #[serde(rename = "ParentID")]
+    pub parent_ids: Vec<String>,
+    #[serde(rename = "ChildID")]
+    pub child_ids: Vec<String>,
+    #[serde(rename = "Information")]
+    pub information: ClassificationInfo,
+}
+
+/// The placement of a compound in one classification hierarchy, from a single source.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ClassificationHierarchy {
+    /// E.g. "MeSH", "ChEBI".
+    #[serde(rename = "SourceName")]
+    pub source_name: String,
+    #[serde(rename = "SourceID")]
+    pub source_id: String,
+    #[serde(rename = "HID")]
+    pub hid: Option<u32>,
+    #[serde(rename = "Information")]
+    pub information: ClassificationInfo,
+    /// The nodes containing the compound, and their ancestors.
+    #[serde(rename = "Node")]
+    pub nodes: Vec<ClassificationNode>,
+}
+
+impl ClassificationHierarchy {
+    pub fn node(&self, node_id: &str) -> Option<&ClassificationNode> {
+        self.nodes.iter().find(|n| n.node_id == node_id)
+    }
+
+    /// The most specific nodes: Those that aren't a parent of another node in this hierarchy.
+    pub fn leaves(&self) -> Vec<&ClassificationNode> {
+        self.nodes
+            .iter()
+            .filter(|n| !self.nodes.iter().any(|o| o.parent_ids.contains(&n.node_id)))
+            .collect()
+    }
+
+    /// The path from the root to a node, inclusive, following the first parent at each level.
+    pub fn lineage(&self, node_id: &str) -> Vec<&ClassificationNode> {
+        let mut result = Vec::new();
+        let mut current = self.node(node_id);
+
+        while let Some(node) = current {
+            // Guard against cycles in malformed data.
+            if result
+                .iter()
+                .any(|n: &&ClassificationNode| n.node_id == node.node_id)
+            {
+                break;
+            }
+            result.push(node);
+            current = node.parent_ids.first().and_then(|p| self.node(p));
+        }
+
+        result.reverse();
+        result
+    }
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ClassificationHierarchies {
+    #[serde(rename = "Hierarchy", default)]
+    hierarchy: Vec<ClassificationHierarchy>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ClassificationResp {
+    #[serde(rename = "Hierarchies")]
+    hierarchies: ClassificationHierarchies,
+}
+
+/// The patent list for common compounds can be tens of MB; allow longer than `HTTP_TIMEOUT`, but
+/// don't wait indefinitely.
+const PATENT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Get the IDs of patents that mention a compound, e.g. "US-2005059672-A1". This list can be very
+/// large for common compounds. PubChem doesn't paginate xrefs (`listkey_start` and `listkey_count`
+/// only apply to identifier lists), so we stream the full response without the usual size limit,
+/// and with a longer timeout.
+pub fn get_patent_ids(cid: u32) -> Result<Vec<String>, ReqError> {
+    let op_spec = OpSpecCompound::Xrefs(vec![XrefType::PatentId]);
+    let url = format!("{BASE_PUG_URL}/compound/cid/{cid}/{op_spec}/JSON");
+
+    let agent = make_agent_pubchem_download();
+    let resp = agent
+        .get(url)
+        .config()
+        .timeout_global(Some(PATENT_TIMEOUT))
+        .build()
+        .call()?;
+
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound {
+            ident: cid.to_string(),
+        });
+    }
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    let parsed: XrefsResp = serde_json::from_reader(resp.into_body().into_reader())?;
+
+    Ok(parsed
+        .information_list
+        .information
+        .into_iter()
+        .flat_map(|x| x.patent_ids)
+        .collect())
+}
+
+/// Get the classification hierarchies a compound is placed in, e.g. MeSH, and ChEBI ontology.
+/// This can be used to group compounds by chemical class.
+pub fn get_classification(cid: u32) -> Result<Vec<ClassificationHierarchy>, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Cid),
+        &[cid.to_string()],
+        OperationSpecification::Compound(OpSpecCompound::Classification),
+    )?;
+
+    let parsed: ClassificationResp = serde_json::from_str(&resp)?;
+    Ok(parsed.hierarchies.hierarchy)
+}
+
+/// A description of a compound, attributed to its source.
+#[derive(Clone, Debug)]
+pub struct DescriptionEntry {
+    pub text: String,
+    pub source_name: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CompoundDescription {
+    pub cid: u32,
+    pub title: Option<String>,
+    pub descriptions: Vec<DescriptionEntry>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct DescriptionInfo {
+    #[serde(rename = "Title")]
+    title: Option<String>,
+    #[serde(rename = "Description")]
+    description: Option<String>,
+    #[serde(rename = "DescriptionSourceName")]
+    description_source_name: Option<String>,
+    #[serde(rename = "DescriptionURL")]
+    description_url: Option<String>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct DescriptionInfoList {
+    #[serde(rename = "Information", default)]
+    information: Vec<DescriptionInfo>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct DescriptionResp {
+    #[serde(rename = "InformationList")]
+    information_list: DescriptionInfoList,
+}
+
+/// Get a compound's title, and description paragraphs from various sources. Useful for tooltips,
+/// and reports.
+pub fn get_description(cid: u32) -> Result<CompoundDescription, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Cid),
+        &[cid.to_string()],
+        OperationSpecification::Compound(OpSpecCompound::Description),
+    )?;
+
+    let parsed: DescriptionResp = serde_json::from_str(&resp)?;
+
+    // The title and descriptions are in separate entries.
+    let mut result = CompoundDescription {
+        cid,
+        ..Default::default()
+    };
+    for info in parsed.information_list.information {
+        if result.title.is_none() {
+            result.title = info.title;
+        }
+        if let Some(text) = info.description {
+            result.descriptions.push(DescriptionEntry {
+                text,
+                source_name: info.description_source_name,
+                url: info.description_url,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Deserializing only. PubChem returns a single ID for the input record, and a list for the output.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(u32),
+    Many(Vec<u32>),
+}
+
+/// Deserializing only
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct IdList {
+    #[serde(rename = "CID")]
+    cid: Option<OneOrMany>,
+    #[serde(rename = "SID")]
+    sid: Option<OneOrMany>,
+    #[serde(rename = "AID")]
+    aid: Option<OneOrMany>,
+}
+
+/// Deserializing only. Identifier operations return an `IdentifierList` or `InformationList`,
+/// depending on the input.
+#[derive(Debug, Deserialize)]
+enum IdListResp {
+    IdentifierList(IdList),
+    InformationList {
+        #[serde(rename = "Information", default)]
+        information: Vec<IdList>,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum IdKind {
+    Cid,
+    Sid,
+    Aid,
+}
+
+/// Collect the output IDs of a given kind from an identifier operation.
+fn parse_id_list(resp: &str, kind: IdKind) -> Result<Vec<u32>, ReqError> {
+    let lists = match serde_json::from_str(resp)? {
+        IdListResp::IdentifierList(l) => vec![l],
+        IdListResp::InformationList { information } => information,
+    };
+
+    let mut result = Vec::new();
+    for list in lists {
+        let ids = match kind {
+            IdKind::Cid => list.cid,
+            IdKind::Sid => list.sid,
+            IdKind::Aid => list.aid,
+        };
+        match ids {
+            Some(OneOrMany::One(id)) => result.push(id),
+            Some(OneOrMany::Many(ids)) => result.extend(ids),
+            None => (),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Get the substances (SIDs) deposited for a compound.
+pub fn sids_for_cid(cid: u32) -> Result<Vec<u32>, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Cid),
+        &[cid.to_string()],
+        OperationSpecification::Compound(OpSpecCompound::Sids),
+    )?;
+
+    parse_id_list(&resp, IdKind::Sid)
+}
+
+/// Get the standardized compounds (CIDs) for a substance.
+pub fn cids_for_sid(sid: u32) -> Result<Vec<u32>, ReqError> {
+    let resp = url_api_query(
+        Domain::Substance,
+        Namespace::Substance(NamespaceSubstance::Sid),
+        &[sid.to_string()],
+        OperationSpecification::Substance(OpSpecSubstance::Cids),
+    )?;
+
+    parse_id_list(&resp, IdKind::Cid)
+}
+
+/// Get the assays (AIDs) a compound has been tested in.
+pub fn aids_for_cid(cid: u32) -> Result<Vec<u32>, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Cid),
+        &[cid.to_string()],
+        OperationSpecification::Compound(OpSpecCompound::Aids),
+    )?;
+
+    parse_id_list(&resp, IdKind::Aid)
+}
+
+/// Find CIDs of compounds with a mass in a range, in Daltons, inclusive. For example, for matching
+/// an observed m/z value from mass spectrometry to candidate compounds, use `MassKind::Monoisotopic`,
+/// and a range of the value plus or minus the instrument's tolerance.
+pub fn cids_by_mass(kind: MassKind, min: f64, max: f64) -> Result<Vec<u32>, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Mass(kind)),
+        &[format!("range/{min}/{max}")],
+        OperationSpecification::Compound(OpSpecCompound::Cids),
+    )?;
+
+    let parsed: SimilarMolsResp = serde_json::from_str(&resp)?;
+    Ok(parsed.identifier_list.cid)
+}
+
+/// Formats for structure input.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StructureFormat {
+    Smiles,
+    Sdf,
+    Inchi,
+}
+
+impl Display for StructureFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Smiles => "smiles",
+            Self::Sdf => "sdf",
+            Self::Inchi => "inchi",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// A structure, standardized to PubChem's conventions.
+#[derive(Clone, Debug)]
+pub struct StandardizedStructure {
+    /// The CID of the matching compound, if it's in PubChem.
+    pub cid: Option<u32>,
+    pub sdf: String,
+}
+
+/// Standardize an arbitrary structure (e.g. user-drawn) using PubChem's standardization service;
+/// the same process used to create compounds from deposited substances. This canonicalizes
+/// tautomers, charges, aromaticity etc, and finds the matching CID, if there is one.
+pub fn standardize(
+    structure: &str,
+    format: StructureFormat,
+) -> Result<StandardizedStructure, ReqError> {
+    let url = format!("{BASE_PUG_URL}/standardize/{format}/SDF");
+
+    let agent = make_agent_pubchem();
+    let mut resp = agent
+        .post(url)
+        .send_form([(format.to_string(), structure)])?;
+    // E.g. a structure PubChem can't parse, or rejects. The body is a fault message; not an SDF.
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+    let sdf = resp.body_mut().read_to_string()?;
+
+    // The first line of the Molfile header is the CID; 0 if there is no match.
+    let cid = sdf
+        .lines()
+        .next()
+        .and_then(|l| l.trim().parse().ok())
+        .filter(|&cid| cid != 0);
+
+    Ok(StandardizedStructure { cid, sdf })
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct SynonymsInfo {
+    #[serde(rename = "Synonym", default)]
+    synonym: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct SynonymsInfoList {
+    #[serde(rename = "Information")]
+    information: Vec<SynonymsInfo>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct SynonymsResp {
+    #[serde(rename = "InformationList")]
+    information_list: SynonymsInfoList,
+}
+
+/// Get all synonyms (names) of a compound, e.g. common names, trade names, and registry IDs.
+/// The first is usually the preferred name.
+pub fn get_synonyms(cid: u32) -> Result<Vec<String>, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Cid),
+        &[cid.to_string()],
+        OperationSpecification::Compound(OpSpecCompound::Synonyms),
     )?;
 
-    let parsed: SimilarMolsResp = serde_json::from_str(&resp)?;
-    Ok(parsed.identifier_list.cid)
+    let parsed: SynonymsResp = serde_json::from_str(&resp)?;
+
+    Ok(parsed
+        .information_list
+        .information
+        .into_iter()
+        .flat_map(|i| i.synonym)
+        .collect())
+}
+
+/// A GHS hazard statement, e.g. code "H302", with text "Harmful if swallowed".
+#[derive(Clone, Debug, PartialEq)]
+pub struct HazardStatement {
+    pub code: String,
+    /// The statement as provided, which may include the percentage of notifications, and the
+    /// hazard class, e.g. "H302 (99.8%): Harmful if swallowed [Warning Acute toxicity, oral]".
+    pub text: String,
+}
+
+/// Globally Harmonized System (GHS) hazard classification, aggregated across all sources
+/// reporting it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GhsClassification {
+    /// Pictogram names, e.g. "Irritant", "Health Hazard".
+    pub pictograms: Vec<String>,
+    /// "Danger", or "Warning". If sources differ, this is "Danger" if any report it.
+    pub signal_word: Option<String>,
+    pub hazard_statements: Vec<HazardStatement>,
+    /// E.g. "P264", "P270".
+    pub precautionary_codes: Vec<String>,
+}
+
+fn push_unique(v: &mut Vec<String>, item: &str) {
+    let item = item.trim();
+    if !item.is_empty() && !v.iter().any(|x| x == item) {
+        v.push(item.to_owned());
+    }
+}
+
+/// Get the GHS hazard classification of a compound: pictograms, signal word, hazard
+/// statements, and precautionary statement codes. This is from the "Safety and Hazards" section
+/// of the PUG-View record. Returns an empty classification if none is reported.
+pub fn get_ghs_classification(cid: u32) -> Result<GhsClassification, ReqError> {
+    // Most compounds have no GHS heading; PubChem reports this as not found.
+    let record = match pug_view::load_section(cid, "GHS Classification") {
+        Ok(r) => r,
+        Err(ReqError::NotFound { .. }) => return Ok(GhsClassification::default()),
+        Err(e) => return Err(e),
+    };
+
+    let mut result = GhsClassification::default();
+
+    let Some(section) = record.find_section("GHS Classification") else {
+        return Ok(result);
+    };
+
+    for info in &section.information {
+        let Some(name) = &info.name else {
+            continue;
+        };
+
+        match name.as_str() {
+            "Pictogram(s)" => {
+                for s in &info.value.string_with_markup {
+                    for markup in &s.markup {
+                        if let Some(extra) = &markup.extra {
+                            push_unique(&mut result.pictograms, extra);
+                        }
+                    }
+                }
+            }
+            "Signal" => {
+                for s in &info.value.string_with_markup {
+                    let signal = s.string.trim();
+                    if result.signal_word.as_deref() != Some("Danger") && !signal.is_empty() {
+                        result.signal_word = Some(signal.to_owned());
+                    }
+                }
+            }
+            "GHS Hazard Statements" => {
+                for s in &info.value.string_with_markup {
+                    let text = s.string.trim();
+                    let code = text
+                        .split(|c: char| c.is_whitespace() || c == ':')
+                        .next()
+                        .unwrap_or_default();
+
+                    if !code.starts_with('H')
+                        || result.hazard_statements.iter().any(|h| h.code == code)
+                    {
+                        continue;
+                    }
+
+                    result.hazard_statements.push(HazardStatement {
+                        code: code.to_owned(),
+                        text: text.to_owned(),
+                    });
+                }
+            }
+            "Precautionary Statement Codes" => {
+                for s in &info.value.string_with_markup {
+                    for code in s.string.split([',', ' ']) {
+                        let code = code.trim();
+                        // Skip conjunctions, e.g. "P264, P270, and P301+P317".
+                        if code.starts_with('P') {
+                            push_unique(&mut result.precautionary_codes, code);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ConformersInfo {
+    #[serde(rename = "ConformerID", default)]
+    conformer_id: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ConformersInfoList {
+    #[serde(rename = "Information")]
+    information: Vec<ConformersInfo>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ConformersResp {
+    #[serde(rename = "InformationList")]
+    information_list: ConformersInfoList,
+}
+
+/// Get the IDs of a compound's 3D conformers, e.g. "000008C400000001". Load each using
+/// [`load_conformer`], or [`load_conformer_sdf`]. Returns an empty Vec if the compound has no
+/// 3D conformers, e.g. for large or inorganic molecules.
+pub fn get_conformers(cid: u32) -> Result<Vec<String>, ReqError> {
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Cid),
+        &[cid.to_string()],
+        OperationSpecification::Compound(OpSpecCompound::Conformers),
+    )?;
+
+    let parsed: ConformersResp = serde_json::from_str(&resp)?;
+
+    Ok(parsed
+        .information_list
+        .information
+        .into_iter()
+        .flat_map(|i| i.conformer_id)
+        .collect())
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ConformerAtoms {
+    aid: Vec<u32>,
+    element: Vec<u8>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ConformerCoordsXyz {
+    x: Vec<f32>,
+    y: Vec<f32>,
+    z: Option<Vec<f32>>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ConformerCoords {
+    aid: Vec<u32>,
+    conformers: Vec<ConformerCoordsXyz>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ConformerCompound {
+    atoms: ConformerAtoms,
+    coords: Vec<ConformerCoords>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct ConformerResp {
+    #[serde(rename = "PC_Compounds")]
+    pc_compounds: Vec<ConformerCompound>,
+}
+
+/// An atom of a 3D conformer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConformerAtom {
+    /// 1-based atom ID.
+    pub aid: u32,
+    /// Atomic number.
+    pub element: u8,
+    /// Å
+    pub posit: [f32; 3],
+}
+
+fn get_conformer(conformer_id: &str, format: OutputFormat) -> Result<String, ReqError> {
+    let agent = make_agent_pubchem();
+    let url = format!("{BASE_PUG_URL}/conformers/{conformer_id}/{format}");
+
+    let mut resp = agent.get(url).call()?;
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound {
+            ident: conformer_id.to_owned(),
+        });
+    }
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    Ok(resp.body_mut().read_to_string()?)
+}
+
+/// Load a single 3D conformer, by its ID, with atom coordinates.
+pub fn load_conformer(conformer_id: &str) -> Result<Vec<ConformerAtom>, ReqError> {
+    let resp = get_conformer(conformer_id, OutputFormat::Json)?;
+    let parsed: ConformerResp = serde_json::from_str(&resp)?;
+
+    let compound = parsed
+        .pc_compounds
+        .into_iter()
+        .next()
+        .ok_or(ReqError::Deserialize)?;
+    let coords = compound.coords.first().ok_or(ReqError::Deserialize)?;
+    let xyz = coords.conformers.first().ok_or(ReqError::Deserialize)?;
+
+    let mut result = Vec::with_capacity(coords.aid.len());
+    for (i, aid) in coords.aid.iter().enumerate() {
+        let element = compound
+            .atoms
+            .aid
+            .iter()
+            .position(|a| a == aid)
+            .and_then(|j| compound.atoms.element.get(j))
+            .copied()
+            .ok_or(ReqError::Deserialize)?;
+
+        let z = xyz.z.as_ref().and_then(|z| z.get(i)).copied().unwrap_or(0.);
+
+        result.push(ConformerAtom {
+            aid: *aid,
+            element,
+            posit: [
+                *xyz.x.get(i).ok_or(ReqError::Deserialize)?,
+                *xyz.y.get(i).ok_or(ReqError::Deserialize)?,
+                z,
+            ],
+        });
+    }
+
+    Ok(result)
+}
+
+/// Load a single 3D conformer, by its ID, as an SDF string.
+pub fn load_conformer_sdf(conformer_id: &str) -> Result<String, ReqError> {
+    get_conformer(conformer_id, OutputFormat::Sdf)
+}
+
+/// Deserializing only
+#[derive(Debug, Default, Deserialize)]
+struct AutocompleteTerms {
+    #[serde(default)]
+    compound: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct AutocompleteResp {
+    #[serde(default)]
+    dictionary_terms: AutocompleteTerms,
+}
+
+/// Get compound name suggestions for a partial query, e.g. for typeahead in a search box.
+pub fn autocomplete(query: &str, limit: u32) -> Result<Vec<String>, ReqError> {
+    let mut url = Url::parse(AUTOCOMPLETE_URL).unwrap();
+    url.path_segments_mut().unwrap().push(query).push("json");
+    url.query_pairs_mut()
+        .append_pair("limit", &limit.to_string());
+
+    let agent = make_agent_pubchem();
+    let resp = agent
+        .get(url.as_str())
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+
+    let parsed: AutocompleteResp = serde_json::from_str(&resp)?;
+    Ok(parsed.dictionary_terms.compound)
 }
 
 pub fn open_overview(id: u32) {
@@ -370,7 +2347,7 @@ pub fn open_overview(id: u32) {
 /// which proteins it can bind to. This notably includes PDB urls
 pub fn load_associated_structures(cid: u32) -> Result<Vec<ProteinStructure>, ReqError> {
     let url = format!("{PROTEIN_LOOKUP_URL}/{cid}/JSON");
-    let agent = make_agent();
+    let agent = make_agent_pubchem();
 
     let resp = agent.get(url).call()?.body_mut().read_to_string()?;
 
@@ -378,39 +2355,183 @@ pub fn load_associated_structures(cid: u32) -> Result<Vec<ProteinStructure>, Req
     Ok(parsed.structure.structures)
 }
 
+/// Whether to load 2D (depiction), or 3D (conformer) coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecordType {
+    TwoD,
+    ThreeD,
+}
+
+impl Display for RecordType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::TwoD => "2d",
+            Self::ThreeD => "3d",
+        };
+        write!(f, "{v}")
+    }
+}
+
 /// Note: If id is a u32 CID`, convert to str prior to passing here.
-fn sdf_url(id_type: StructureSearchNamespace, id: &str) -> String {
-    format!("https://pubchem.ncbi.nlm.nih.gov/rest/pug/compound/{id_type}/{id}/SDF?record_type=3d",)
+fn sdf_url(id_type: StructureSearchNamespace, id: &str, record_type: RecordType) -> String {
+    format!(
+        "https://pubchem.ncbi.nlm.nih.gov/rest/pug/compound/{id_type}/{id}/SDF?record_type={record_type}",
+    )
 }
 
-/// Download an SDF file from PubChem, returning an SDF string.
-pub fn load_sdf(id_type: StructureSearchNamespace, id: &str) -> Result<String, ReqError> {
-    let agent = make_agent();
+/// Download an SDF file from PubChem, returning an SDF string, and the record type obtained.
+/// If 3D is requested, but the compound has no 3D conformer (e.g. large or inorganic
+/// molecules), falls back to 2D.
+pub fn load_sdf(
+    id_type: StructureSearchNamespace,
+    id: &str,
+    record_type: RecordType,
+) -> Result<(String, RecordType), ReqError> {
+    let agent = make_agent_pubchem();
 
-    Ok(agent
-        .get(sdf_url(id_type, id))
-        .call()?
-        .body_mut()
-        .read_to_string()?)
+    let mut resp = agent.get(sdf_url(id_type, id, record_type)).call()?;
+
+    if resp.status() == 200 {
+        return Ok((resp.body_mut().read_to_string()?, record_type));
+    }
+
+    if record_type == RecordType::TwoD {
+        return Err(ReqError::Http);
+    }
+
+    let mut resp = agent.get(sdf_url(id_type, id, RecordType::TwoD)).call()?;
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    Ok((resp.body_mut().read_to_string()?, RecordType::TwoD))
 }
 
-/// Get the Simplified Molecular Input Line Entry System (SMILES) representation from an identifier.
-/// This seems to work using pdbE/Amber identifiers. Not technically pubchem, but is
-/// from NIH.gov.
-/// todo: Support SELFEIS too; doesn't seem to be available.
-pub fn get_smiles_chem_name(name: &str) -> Result<String, ReqError> {
-    let agent = make_agent();
-    let url = format!("https://cactus.nci.nih.gov/chemical/structure/{name}/smiles");
+/// The size of 2D structure depictions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageSize {
+    /// 100x100 pixels.
+    Small,
+    /// 300x300 pixels.
+    Large,
+    /// Width, height, in pixels.
+    Custom(u32, u32),
+}
 
-    // Make sure to catch the HTTP != 200, and return an error: Otherwise the result will be an OK with
-    // brief HTML failure message string.
-    let mut resp = agent.get(url).call()?;
+impl Display for ImageSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Small => write!(f, "small"),
+            Self::Large => write!(f, "large"),
+            Self::Custom(w, h) => write!(f, "{w}x{h}"),
+        }
+    }
+}
+
+/// Download a 2D structure depiction of a compound, as PNG bytes. E.g. for thumbnails.
+pub fn load_image(cid: u32, size: ImageSize) -> Result<Vec<u8>, ReqError> {
+    let agent = make_agent_pubchem();
+    let url = format!("{BASE_PUG_URL}/compound/cid/{cid}/PNG?image_size={size}");
 
+    let mut resp = agent.get(url).call()?;
     if resp.status() != 200 {
         return Err(ReqError::Http);
     }
 
-    Ok(resp.body_mut().read_to_string()?)
+    Ok(resp.body_mut().read_to_vec()?)
+}
+
+/// Where a SMILES string came from.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SmilesSource {
+    PubChem,
+    /// The NCI Cactus resolver; used as a fallback.
+    Cactus,
+}
+
+#[derive(Clone, Debug)]
+pub struct Smiles {
+    /// Includes stereochemical and isotopic information.
+    pub isomeric: String,
+    /// Connectivity only, without stereochemical or isotopic information. Not available from Cactus.
+    pub canonical: Option<String>,
+    pub source: SmilesSource,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct SmilesProps {
+    #[serde(rename = "SMILES")]
+    smiles: String,
+    #[serde(rename = "ConnectivitySMILES")]
+    connectivity_smiles: Option<String>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct SmilesPropTable {
+    #[serde(rename = "Properties")]
+    properties: Vec<SmilesProps>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct SmilesPropResp {
+    #[serde(rename = "PropertyTable")]
+    property_table: SmilesPropTable,
+}
+
+fn smiles_pubchem(namespace: NamespaceCompound, ident: &str) -> Result<Smiles, ReqError> {
+    let props = vec!["SMILES".to_owned(), "ConnectivitySMILES".to_owned()];
+
+    let resp = url_api_query(
+        Domain::Compound,
+        Namespace::Compound(namespace),
+        &[ident.to_owned()],
+        OperationSpecification::Compound(OpSpecCompound::Property(props)),
+    )?;
+
+    let parsed: SmilesPropResp = serde_json::from_str(&resp)?;
+    let row = parsed
+        .property_table
+        .properties
+        .into_iter()
+        .next()
+        .ok_or(ReqError::NotFound {
+            ident: ident.to_owned(),
+        })?;
+
+    Ok(Smiles {
+        isomeric: row.smiles,
+        canonical: row.connectivity_smiles,
+        source: SmilesSource::PubChem,
+    })
+}
+
+/// Get canonical and isomeric SMILES for a compound name, or other identifier. Uses PubChem; if it
+/// doesn't know the identifier (e.g. some PDBe/Amber ligand identifiers), falls back to the NCI
+/// Cactus resolver. The result is annotated with its source.
+pub fn get_smiles_by_name(name: &str) -> Result<Smiles, ReqError> {
+    match smiles_pubchem(NamespaceCompound::Name, name) {
+        Ok(s) => Ok(s),
+        Err(_) => Ok(Smiles {
+            isomeric: cactus::get_smiles(name)?,
+            canonical: None,
+            source: SmilesSource::Cactus,
+        }),
+    }
+}
+
+/// Get the Simplified Molecular Input Line Entry System (SMILES) representation from an identifier.
+/// See [`get_smiles_by_name`] for details. For PDBe ligand codes, use [`cactus::get_smiles`]
+/// directly, since those may collide with unrelated PubChem names.
+pub fn get_smiles_chem_name(name: &str) -> Result<String, ReqError> {
+    Ok(get_smiles_by_name(name)?.isomeric)
+}
+
+/// Get canonical and isomeric SMILES from a PubChem CID.
+pub fn get_smiles_full(cid: u32) -> Result<Smiles, ReqError> {
+    smiles_pubchem(NamespaceCompound::Cid, &cid.to_string())
 }
 
 fn pubchem_smiles_url(cid: u32) -> String {
@@ -419,7 +2540,7 @@ fn pubchem_smiles_url(cid: u32) -> String {
 
 /// Get SMILES directly from a PubChem CID via PUG-REST.
 pub fn get_smiles(cid: u32) -> Result<String, ReqError> {
-    let agent = make_agent();
+    let agent = make_agent_pubchem();
     let url = pubchem_smiles_url(cid);
 
     let mut resp = agent.get(url).call()?;
@@ -509,7 +2630,7 @@ struct PropertyTableInner {
 
 /// Get properties from an ID.
 pub fn properties(id_type: StructureSearchNamespace, id: &str) -> Result<Properties, ReqError> {
-    let agent = make_agent();
+    let agent = make_agent_pubchem();
     let url = properties_url(id_type, id);
 
     let mut resp = agent.get(url).call()?;
@@ -538,63 +2659,698 @@ pub fn properties(id_type: StructureSearchNamespace, id: &str) -> Result<Propert
     })
 }
 
+/// Compound properties that can be requested by [`get_properties_batch`].
+/// [Properties list](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Compound-Property-Tables)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Property {
+    MolecularFormula,
+    MolecularWeight,
+    /// Includes stereochemical and isotopic information.
+    Smiles,
+    ConnectivitySmiles,
+    Inchi,
+    InchiKey,
+    IupacName,
+    Title,
+    XLogP,
+    ExactMass,
+    MonoisotopicMass,
+    Tpsa,
+    Complexity,
+    Charge,
+    HBondDonorCount,
+    HBondAcceptorCount,
+    RotatableBondCount,
+    HeavyAtomCount,
+    Volume3d,
+}
+
+impl Display for Property {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::MolecularFormula => "MolecularFormula",
+            Self::MolecularWeight => "MolecularWeight",
+            Self::Smiles => "SMILES",
+            Self::ConnectivitySmiles => "ConnectivitySMILES",
+            Self::Inchi => "InChI",
+            Self::InchiKey => "InChIKey",
+            Self::IupacName => "IUPACName",
+            Self::Title => "Title",
+            Self::XLogP => "XLogP",
+            Self::ExactMass => "ExactMass",
+            Self::MonoisotopicMass => "MonoisotopicMass",
+            Self::Tpsa => "TPSA",
+            Self::Complexity => "Complexity",
+            Self::Charge => "Charge",
+            Self::HBondDonorCount => "HBondDonorCount",
+            Self::HBondAcceptorCount => "HBondAcceptorCount",
+            Self::RotatableBondCount => "RotatableBondCount",
+            Self::HeavyAtomCount => "HeavyAtomCount",
+            Self::Volume3d => "Volume3D",
+        };
+        write!(f, "{v}")
+    }
+}
+
+/// Properties of a compound. Fields that weren't requested, or that PubChem has no value for,
+/// are `None`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CompoundProperties {
+    #[serde(rename = "CID")]
+    pub cid: u32,
+    #[serde(rename = "MolecularFormula")]
+    pub molecular_formula: Option<String>,
+    #[serde(
+        rename = "MolecularWeight",
+        deserialize_with = "deserialize_option_number_from_string"
+    )]
+    pub molecular_weight: Option<f32>,
+    #[serde(rename = "SMILES")]
+    pub smiles: Option<String>,
+    #[serde(rename = "ConnectivitySMILES")]
+    pub connectivity_smiles: Option<String>,
+    #[serde(rename = "InChI")]
+    pub inchi: Option<String>,
+    #[serde(rename = "InChIKey")]
+    pub inchi_key: Option<String>,
+    #[serde(rename = "IUPACName")]
+    pub iupac_name: Option<String>,
+    #[serde(rename = "Title")]
+    pub title: Option<String>,
+    #[serde(rename = "XLogP")]
+    pub xlogp: Option<f32>,
+    #[serde(
+        rename = "ExactMass",
+        deserialize_with = "deserialize_option_number_from_string"
+    )]
+    pub exact_mass: Option<f32>,
+    #[serde(
+        rename = "MonoisotopicMass",
+        deserialize_with = "deserialize_option_number_from_string"
+    )]
+    pub monoisotopic_mass: Option<f32>,
+    #[serde(rename = "TPSA")]
+    pub tpsa: Option<f32>,
+    #[serde(rename = "Complexity")]
+    pub complexity: Option<f32>,
+    #[serde(rename = "Charge")]
+    pub charge: Option<i32>,
+    #[serde(rename = "HBondDonorCount")]
+    pub h_bond_donor_count: Option<u32>,
+    #[serde(rename = "HBondAcceptorCount")]
+    pub h_bond_acceptor_count: Option<u32>,
+    #[serde(rename = "RotatableBondCount")]
+    pub rotatable_bond_count: Option<u32>,
+    #[serde(rename = "HeavyAtomCount")]
+    pub heavy_atom_count: Option<u32>,
+    #[serde(rename = "Volume3D")]
+    pub volume_3d: Option<f32>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct BatchPropertyTable {
+    #[serde(rename = "Properties", default)]
+    properties: Vec<CompoundProperties>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct BatchPropertyResp {
+    #[serde(rename = "PropertyTable")]
+    property_table: BatchPropertyTable,
+}
+
+/// The number of CIDs per request in [`get_properties_batch`]. We POST the CIDs, so there's no
+/// URL length limit, but large requests risk PubChem's 30-second processing timeout.
+const PROPERTY_BATCH_SIZE: usize = 500;
+
+/// Load properties for many compounds. CIDs are sent in chunks, in POST requests. Returns a result
+/// per CID, in the order requested; e.g. a CID that doesn't exist results in a `NotFound` error,
+/// without affecting the others.
+pub fn get_properties_batch(
+    cids: &[u32],
+    properties: &[Property],
+) -> Vec<(u32, Result<CompoundProperties, ReqError>)> {
+    let props: Vec<_> = properties.iter().map(|p| p.to_string()).collect();
+
+    let mut result = Vec::with_capacity(cids.len());
+
+    for chunk in cids.chunks(PROPERTY_BATCH_SIZE) {
+        properties_chunk(chunk, &props, &mut result);
+    }
+
+    result
+}
+
+/// Load properties for one chunk of CIDs, appending a result per CID. If the request fails for a
+/// reason other than none of the CIDs existing, we bisect the chunk; this isolates the CIDs that
+/// cause the failure, and reports the real error for each of them.
+fn properties_chunk(
+    chunk: &[u32],
+    props: &[String],
+    result: &mut Vec<(u32, Result<CompoundProperties, ReqError>)>,
+) {
+    let idents: Vec<_> = chunk.iter().map(|c| c.to_string()).collect();
+
+    let resp = post_compound_query(
+        NamespaceCompound::Cid,
+        "cid",
+        &idents.join(","),
+        OpSpecCompound::Property(props.to_vec()),
+        &OpOptions::default(),
+    )
+    .and_then(|r| Ok(serde_json::from_str::<BatchPropertyResp>(&r)?));
+
+    match resp {
+        Ok(parsed) => {
+            let mut rows = parsed.property_table.properties;
+            for &cid in chunk {
+                let row = match rows.iter().position(|r| r.cid == cid) {
+                    Some(i) => Ok(rows.swap_remove(i)),
+                    None => Err(ReqError::NotFound {
+                        ident: cid.to_string(),
+                    }),
+                };
+                result.push((cid, row));
+            }
+        }
+        Err(ReqError::NotFound { .. }) => {
+            for &cid in chunk {
+                result.push((
+                    cid,
+                    Err(ReqError::NotFound {
+                        ident: cid.to_string(),
+                    }),
+                ));
+            }
+        }
+        Err(e) if chunk.len() == 1 => result.push((chunk[0], Err(e))),
+        Err(_) => {
+            let (a, b) = chunk.split_at(chunk.len() / 2);
+            properties_chunk(a, props, result);
+            properties_chunk(b, props, result);
+        }
+    }
+}
+
+/// PDBe ligand codes aren't PubChem names (e.g. a 3-letter code may match an unrelated synonym),
+/// so we resolve them via Cactus instead of PubChem's name lookup.
 pub fn properties_from_pdbe_id(pdb_id: &str) -> Result<Properties, ReqError> {
-    let smiles = get_smiles_chem_name(pdb_id)?;
+    let smiles = cactus::get_smiles(pdb_id)?;
     properties(StructureSearchNamespace::Smiles, &smiles)
 }
 
 /// We do this via an intermediate SMILES representation.
 /// Also returns the SMILES, as we load it anyway.
+/// As with [`properties_from_pdbe_id`], this resolves the ligand code via Cactus.
 pub fn get_cid_from_pdbe_id(pdb_id: &str) -> Result<(u32, String), ReqError> {
-    let smiles = get_smiles_chem_name(pdb_id)?;
+    let smiles = cactus::get_smiles(pdb_id)?;
     let cids = find_cids_from_search(&smiles, true)?;
 
     Ok((cids[0], smiles))
 }
 
-#[allow(unused)]
-#[derive(Clone, Debug, Deserialize)]
+/// Deserializing only
+#[derive(Clone, Debug, Default, Deserialize)]
 struct RecordIdB {
-    cid: u32,
+    /// Absent for structures that aren't in PubChem, e.g. standardized user input.
+    cid: Option<u32>,
 }
 
-#[allow(unused)]
-#[derive(Clone, Debug, Deserialize)]
+/// Deserializing only
+#[derive(Clone, Debug, Default, Deserialize)]
 struct RecordIdA {
+    #[serde(default)]
     id: RecordIdB,
 }
 
-#[allow(unused)]
+/// Deserializing only
+#[derive(Clone, Debug, Deserialize)]
+struct PcAtomCharge {
+    aid: u32,
+    value: i8,
+}
+
+/// Deserializing only
+#[derive(Clone, Debug, Default, Deserialize)]
+struct PcAtoms {
+    aid: Vec<u32>,
+    element: Vec<u8>,
+    #[serde(default)]
+    charge: Vec<PcAtomCharge>,
+}
+
+/// Deserializing only
+#[derive(Clone, Debug, Default, Deserialize)]
+struct PcBonds {
+    aid1: Vec<u32>,
+    aid2: Vec<u32>,
+    order: Vec<u8>,
+}
+
+/// Deserializing only
+#[derive(Clone, Debug, Deserialize)]
+struct PcPropUrn {
+    label: String,
+    name: Option<String>,
+}
+
+/// Deserializing only
+#[derive(Clone, Debug, Deserialize)]
+struct PcPropValue {
+    sval: Option<String>,
+    fval: Option<f64>,
+    ival: Option<i64>,
+}
+
+/// Deserializing only
 #[derive(Clone, Debug, Deserialize)]
+struct PcProp {
+    urn: PcPropUrn,
+    value: PcPropValue,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
 struct PcCompound {
     id: RecordIdA,
-    // todo: Other fields A/R.
-    // atoms: Vec<u32>,
+    #[serde(default)]
+    atoms: PcAtoms,
+    #[serde(default)]
+    bonds: PcBonds,
+    #[serde(default)]
+    coords: Vec<ConformerCoords>,
+    #[serde(default)]
+    charge: i32,
+    #[serde(default)]
+    props: Vec<PcProp>,
 }
 
-#[allow(unused)]
-#[derive(Clone, Debug, Deserialize)]
+/// Deserializing only
+#[derive(Debug, Deserialize)]
 struct RecordResp {
     #[serde(rename = "PC_Compounds")]
     pc_compounds: Vec<PcCompound>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordAtom {
+    /// 1-based atom ID.
+    pub aid: u32,
+    /// Atomic number.
+    pub element: u8,
+    /// Formal charge.
+    pub charge: i8,
+    /// Å for 3D records. For 2D records, z is 0, and units are arbitrary.
+    pub posit: Option<[f32; 3]>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordBond {
+    pub aid1: u32,
+    pub aid2: u32,
+    /// 1: single, 2: double, 3: triple, 4: quadruple, 5: dative, 6: complex, 7: ionic,
+    /// 255: unknown. Aromatic bonds are represented in Kekulé form.
+    pub order: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropValue {
+    Str(String),
+    Float(f64),
+    Int(i64),
+}
+
+/// A computed property included in the record, e.g. label "IUPAC Name", name "Preferred".
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordProperty {
+    pub label: String,
+    pub name: Option<String>,
+    pub value: PropValue,
+}
+
+/// A full PubChem compound record: Atoms, bonds, coordinates, and computed properties. This
+/// contains enough to build a molecule, without downloading an SDF.
+#[derive(Clone, Debug, Default)]
+pub struct CompoundRecord {
+    pub cid: Option<u32>,
+    /// Net charge.
+    pub charge: i32,
+    pub atoms: Vec<RecordAtom>,
+    pub bonds: Vec<RecordBond>,
+    pub props: Vec<RecordProperty>,
+}
+
+impl CompoundRecord {
+    /// Find a property by label, and optionally name. E.g. `prop("SMILES", Some("Absolute"))`.
+    pub fn prop(&self, label: &str, name: Option<&str>) -> Option<&PropValue> {
+        self.props
+            .iter()
+            .find(|p| p.label == label && (name.is_none() || p.name.as_deref() == name))
+            .map(|p| &p.value)
+    }
+}
+
+impl From<PcCompound> for CompoundRecord {
+    fn from(raw: PcCompound) -> Self {
+        // We use the first conformer, if present.
+        let coords = raw
+            .coords
+            .first()
+            .and_then(|c| c.conformers.first().map(|xyz| (&c.aid, xyz)));
+
+        let posit = |aid: u32| {
+            let (aids, xyz) = coords?;
+            let i = aids.iter().position(|a| *a == aid)?;
+            let z = xyz.z.as_ref().and_then(|z| z.get(i)).copied().unwrap_or(0.);
+            Some([*xyz.x.get(i)?, *xyz.y.get(i)?, z])
+        };
+
+        let atoms = raw
+            .atoms
+            .aid
+            .iter()
+            .zip(&raw.atoms.element)
+            .map(|(&aid, &element)| RecordAtom {
+                aid,
+                element,
+                charge: raw
+                    .atoms
+                    .charge
+                    .iter()
+                    .find(|c| c.aid == aid)
+                    .map(|c| c.value)
+                    .unwrap_or_default(),
+                posit: posit(aid),
+            })
+            .collect();
+
+        let bonds = raw
+            .bonds
+            .aid1
+            .iter()
+            .zip(&raw.bonds.aid2)
+            .zip(&raw.bonds.order)
+            .map(|((&aid1, &aid2), &order)| RecordBond { aid1, aid2, order })
+            .collect();
+
+        let props = raw
+            .props
+            .into_iter()
+            .filter_map(|p| {
+                let value = if let Some(v) = p.value.sval {
+                    PropValue::Str(v)
+                } else if let Some(v) = p.value.fval {
+                    PropValue::Float(v)
+                } else {
+                    PropValue::Int(p.value.ival?)
+                };
+                Some(RecordProperty {
+                    label: p.urn.label,
+                    name: p.urn.name,
+                    value,
+                })
+            })
+            .collect();
+
+        Self {
+            cid: raw.id.id.cid,
+            charge: raw.charge,
+            atoms,
+            bonds,
+            props,
+        }
+    }
+}
+
+/// Load a full compound record, with 2D or 3D coordinates.
+pub fn load_record(cid: u32, record_type: RecordType) -> Result<CompoundRecord, ReqError> {
+    let options = OpOptions {
+        other: vec![("record_type".to_owned(), record_type.to_string())],
+        ..Default::default()
+    };
+
+    let resp = url_api_query_with_options(
+        Domain::Compound,
+        Namespace::Compound(NamespaceCompound::Cid),
+        &[cid.to_string()],
+        OperationSpecification::Compound(OpSpecCompound::Record),
+        &options,
+    )?;
+
+    let parsed: RecordResp = serde_json::from_str(&resp)?;
+
+    parsed
+        .pc_compounds
+        .into_iter()
+        .next()
+        .map(Into::into)
+        .ok_or(ReqError::NotFound {
+            ident: cid.to_string(),
+        })
+}
+
+/// How names are matched in name searches.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum NameType {
+    /// The name must match a synonym exactly, ignoring case.
+    #[default]
+    Complete,
+    /// Each word in the name must match a word in a synonym.
+    Word,
+}
+
+impl Display for NameType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            Self::Complete => "complete",
+            Self::Word => "word",
+        };
+        write!(f, "{v}")
+    }
+}
+
 /// Load a list of CIDs from a name search
 pub fn find_cids_from_search(name: &str, smiles: bool) -> Result<Vec<u32>, ReqError> {
-    let domain = Domain::Compound; // todo: Compound, Protein, both? Try one then the other?
+    if smiles {
+        // We POST, as SMILES may contain characters that break URL paths.
+        let resp = post_compound_query(
+            NamespaceCompound::Smiles,
+            "smiles",
+            name,
+            OpSpecCompound::Cids,
+            &OpOptions::default(),
+        )?;
+        return parse_id_list(&resp, IdKind::Cid);
+    }
 
-    let nsc = if smiles {
-        NamespaceCompound::Smiles
-    } else {
-        NamespaceCompound::Name
+    find_cids_by_name(name, NameType::Complete)
+}
+
+/// Load a list of CIDs from a name search, e.g. `find_cids_by_name("acid", NameType::Word)`.
+pub fn find_cids_by_name(name: &str, name_type: NameType) -> Result<Vec<u32>, ReqError> {
+    let options = OpOptions {
+        other: vec![("name_type".to_owned(), name_type.to_string())],
+        ..Default::default()
+    };
+
+    // We POST, as names may contain spaces, and other characters that break URL paths.
+    let resp = post_compound_query(
+        NamespaceCompound::Name,
+        "name",
+        name,
+        OpSpecCompound::Cids,
+        &options,
+    )?;
+
+    parse_id_list(&resp, IdKind::Cid)
+}
+
+/// The maximum number of results from [`search_compounds`].
+const SEARCH_MAX_RESULTS: usize = 100;
+
+/// A compound from a name search, with basic properties for display.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CompoundSearchHit {
+    #[serde(rename = "CID")]
+    pub cid: u32,
+    #[serde(rename = "Title")]
+    pub title: Option<String>,
+    #[serde(rename = "MolecularFormula")]
+    pub formula: Option<String>,
+    /// In Daltons.
+    #[serde(
+        rename = "MolecularWeight",
+        default,
+        deserialize_with = "deserialize_option_number_from_string"
+    )]
+    pub molecular_weight: Option<f32>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct SearchHitTable {
+    #[serde(rename = "Properties", default)]
+    properties: Vec<CompoundSearchHit>,
+}
+
+/// Deserializing only
+#[derive(Debug, Deserialize)]
+struct SearchHitResp {
+    #[serde(rename = "PropertyTable")]
+    property_table: SearchHitTable,
+}
+
+/// Search compounds by name, matching words. Returns the first results, with their title,
+/// formula, and molecular weight.
+pub fn search_compounds(name: &str) -> Result<Vec<CompoundSearchHit>, ReqError> {
+    let mut cids = match find_cids_by_name(name, NameType::Word) {
+        Ok(c) => c,
+        Err(ReqError::NotFound { .. }) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
     };
-    let namespace = Namespace::Compound(nsc);
+    cids.truncate(SEARCH_MAX_RESULTS);
+
+    if cids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let cids: Vec<_> = cids.iter().map(|c| c.to_string()).collect();
+    let props = ["Title", "MolecularFormula", "MolecularWeight"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+
+    let resp = post_compound_query(
+        NamespaceCompound::Cid,
+        "cid",
+        &cids.join(","),
+        OpSpecCompound::Property(props),
+        &OpOptions::default(),
+    )?;
+
+    let parsed: SearchHitResp = serde_json::from_str(&resp)?;
+    Ok(parsed.property_table.properties)
+}
+
+/// Element data from PubChem's periodic table. Numerical fields are `None` where PubChem has
+/// no value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    pub atomic_number: u8,
+    pub symbol: String,
+    pub name: String,
+    /// Daltons
+    pub atomic_mass: Option<f32>,
+    /// E.g. "FFFFFF".
+    pub cpk_hex_color: String,
+    /// E.g. "[He]2s2 2p2".
+    pub electron_configuration: String,
+    /// Pauling scale
+    pub electronegativity: Option<f32>,
+    /// pm
+    pub atomic_radius: Option<f32>,
+    /// eV
+    pub ionization_energy: Option<f32>,
+    /// eV
+    pub electron_affinity: Option<f32>,
+    /// E.g. "+4, +2, -4".
+    pub oxidation_states: String,
+    /// E.g. "Gas", "Solid".
+    pub standard_state: String,
+    /// K
+    pub melting_point: Option<f32>,
+    /// K
+    pub boiling_point: Option<f32>,
+    /// g/cm³
+    pub density: Option<f32>,
+    /// E.g. "Nonmetal", "Noble gas".
+    pub group_block: String,
+    pub year_discovered: String,
+}
 
-    let op_spec = OperationSpecification::Compound(OpSpecCompound::Record);
+/// Load data on all elements, e.g. atomic masses and electronegativities.
+pub fn get_periodic_table() -> Result<Vec<Element>, ReqError> {
+    let agent = make_agent_pubchem();
+    let url = format!("{BASE_PUG_URL}/periodictable/JSON");
+
+    let resp = agent.get(url).call()?.body_mut().read_to_string()?;
+    let table = Table::from_json(&resp)?;
+
+    let col = |name| table.col_index(name).ok_or(ReqError::Deserialize);
+
+    let i_num = col("AtomicNumber")?;
+    let i_sym = col("Symbol")?;
+    let i_name = col("Name")?;
+    let i_mass = col("AtomicMass")?;
+    let i_color = col("CPKHexColor")?;
+    let i_config = col("ElectronConfiguration")?;
+    let i_en = col("Electronegativity")?;
+    let i_radius = col("AtomicRadius")?;
+    let i_ie = col("IonizationEnergy")?;
+    let i_ea = col("ElectronAffinity")?;
+    let i_ox = col("OxidationStates")?;
+    let i_state = col("StandardState")?;
+    let i_mp = col("MeltingPoint")?;
+    let i_bp = col("BoilingPoint")?;
+    let i_density = col("Density")?;
+    let i_group = col("GroupBlock")?;
+    let i_year = col("YearDiscovered")?;
+
+    let mut result = Vec::with_capacity(table.rows.len());
+    for row in &table.rows {
+        let text = |i: usize| row.get(i).cloned().unwrap_or_default();
+        let num = |i: usize| row.get(i).and_then(|v| v.trim().parse().ok());
+
+        result.push(Element {
+            atomic_number: num(i_num).ok_or(ReqError::Deserialize)? as u8,
+            symbol: text(i_sym),
+            name: text(i_name),
+            atomic_mass: num(i_mass),
+            cpk_hex_color: text(i_color),
+            electron_configuration: text(i_config),
+            electronegativity: num(i_en),
+            atomic_radius: num(i_radius),
+            ionization_energy: num(i_ie),
+            electron_affinity: num(i_ea),
+            oxidation_states: text(i_ox),
+            standard_state: text(i_state),
+            melting_point: num(i_mp),
+            boiling_point: num(i_bp),
+            density: num(i_density),
+            group_block: text(i_group),
+            year_discovered: text(i_year),
+        });
+    }
 
-    let data = url_api_query(domain, namespace, &[name.to_string()], op_spec)?;
+    Ok(result)
+}
 
-    let result: RecordResp = serde_json::from_str(&data)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(result.pc_compounds.iter().map(|p| p.id.id.cid).collect())
+    #[test]
+    fn pug_url_encoding() {
+        let options = OpOptions {
+            other: vec![("name_type".to_owned(), "word".to_owned())],
+            ..Default::default()
+        };
+        let url = pug_url(
+            &["compound".to_owned(), "fastsimilarity_3d/cid".to_owned()],
+            Some("acetic acid"),
+            &[
+                "property/Title,MolecularWeight".to_owned(),
+                "JSON".to_owned(),
+            ],
+            &options,
+        );
+
+        assert_eq!(
+            url.as_str(),
+            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/compound/fastsimilarity_3d/cid/acetic%20acid/property/Title,MolecularWeight/JSON?name_type=word"
+        );
+    }
 }
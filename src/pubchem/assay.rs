@@ -0,0 +1,281 @@
+//! Typed access to the PubChem BioAssay (AID) domain.
+//! [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Assays)
+
+use serde::Deserialize;
+
+use crate::{
+    ReqError,
+    pubchem::{
+        AssayTargetType, Domain, Namespace, NamespaceAssay, OpSpecAssay, OperationSpecification,
+        Table, url_api_query,
+    },
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AssayTarget {
+    #[serde(rename = "Accession")]
+    pub accession: Option<String>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "GI")]
+    pub gi: Option<u64>,
+    #[serde(rename = "GeneID")]
+    pub gene_id: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AssaySummary {
+    #[serde(rename = "AID")]
+    pub aid: u32,
+    #[serde(rename = "SourceName")]
+    pub source_name: Option<String>,
+    #[serde(rename = "SourceID")]
+    pub source_id: Option<String>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Description", default)]
+    pub description: Vec<String>,
+    #[serde(rename = "Protocol", default)]
+    pub protocol: Vec<String>,
+    #[serde(rename = "Comment", default)]
+    pub comment: Vec<String>,
+    /// E.g. "confirmatory", "screening".
+    #[serde(rename = "Method")]
+    pub method: Option<String>,
+    #[serde(rename = "Target", default)]
+    pub targets: Vec<AssayTarget>,
+    #[serde(rename = "CIDCountAll")]
+    pub cid_count_all: Option<u32>,
+    #[serde(rename = "CIDCountActive")]
+    pub cid_count_active: Option<u32>,
+    #[serde(rename = "CIDCountInactive")]
+    pub cid_count_inactive: Option<u32>,
+    #[serde(rename = "SIDCountAll")]
+    pub sid_count_all: Option<u32>,
+    #[serde(rename = "SIDCountActive")]
+    pub sid_count_active: Option<u32>,
+    #[serde(rename = "SIDCountInactive")]
+    pub sid_count_inactive: Option<u32>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct AssaySummariesInner {
+    #[serde(rename = "AssaySummary")]
+    assay_summary: Vec<AssaySummary>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct AssaySummariesResp {
+    #[serde(rename = "AssaySummaries")]
+    assay_summaries: AssaySummariesInner,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AssayDescrAidId {
+    id: u32,
+    version: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AssayDescrSourceDb {
+    name: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AssayDescrSource {
+    db: Option<AssayDescrSourceDb>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AssayDescrRaw {
+    aid: AssayDescrAidId,
+    aid_source: Option<AssayDescrSource>,
+    name: Option<String>,
+    #[serde(default)]
+    description: Vec<String>,
+    #[serde(default)]
+    protocol: Vec<String>,
+    #[serde(default)]
+    comment: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AssayDescrWrapper {
+    descr: AssayDescrRaw,
+}
+
+#[derive(Deserialize)]
+struct AssayContainerItem {
+    assay: AssayDescrWrapper,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct AssayDescriptionResp {
+    #[serde(rename = "PC_AssayContainer")]
+    pc_assay_container: Vec<AssayContainerItem>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AssayDescription {
+    pub aid: u32,
+    pub version: Option<u32>,
+    pub source_name: Option<String>,
+    pub name: Option<String>,
+    pub description: Vec<String>,
+    pub protocol: Vec<String>,
+    pub comment: Vec<String>,
+}
+
+/// Target identifiers of an assay.
+#[derive(Clone, Debug, Deserialize)]
+pub struct AssayTargetInfo {
+    #[serde(rename = "AID")]
+    pub aid: u32,
+    #[serde(rename = "GI", default)]
+    pub gi: Vec<u64>,
+    #[serde(rename = "ProteinName", default)]
+    pub protein_name: Vec<String>,
+    #[serde(rename = "GeneID", default)]
+    pub gene_id: Vec<u32>,
+    #[serde(rename = "GeneSymbol", default)]
+    pub gene_symbol: Vec<String>,
+    #[serde(rename = "Accession", default)]
+    pub accession: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct AssayTargetInfoList {
+    #[serde(rename = "Information")]
+    information: Vec<AssayTargetInfo>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct AssayTargetResp {
+    #[serde(rename = "InformationList")]
+    information_list: AssayTargetInfoList,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct AidList {
+    #[serde(rename = "AID", default)]
+    aid: Vec<u32>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct AidListResp {
+    #[serde(rename = "IdentifierList")]
+    identifier_list: AidList,
+}
+
+fn query(namespace: NamespaceAssay, idents: &[u32], op: OpSpecAssay) -> Result<String, ReqError> {
+    let idents: Vec<_> = idents.iter().map(|i| i.to_string()).collect();
+
+    url_api_query(
+        Domain::Assay,
+        Namespace::Assay(namespace),
+        &idents,
+        OperationSpecification::Assay(op),
+    )
+}
+
+/// Load summaries of one or more assays: Name, source, targets, and active/inactive counts.
+pub fn get_summaries(aids: &[u32]) -> Result<Vec<AssaySummary>, ReqError> {
+    let resp = query(NamespaceAssay::Aid, aids, OpSpecAssay::Summary)?;
+    let parsed: AssaySummariesResp = serde_json::from_str(&resp)?;
+    Ok(parsed.assay_summaries.assay_summary)
+}
+
+/// Load the description of an assay, including its protocol.
+pub fn get_description(aid: u32) -> Result<AssayDescription, ReqError> {
+    let resp = query(NamespaceAssay::Aid, &[aid], OpSpecAssay::Description)?;
+    let parsed: AssayDescriptionResp = serde_json::from_str(&resp)?;
+
+    let descr = parsed
+        .pc_assay_container
+        .into_iter()
+        .next()
+        .ok_or(ReqError::Deserialize)?
+        .assay
+        .descr;
+
+    Ok(AssayDescription {
+        aid: descr.aid.id,
+        version: descr.aid.version,
+        source_name: descr.aid_source.and_then(|s| s.db).and_then(|d| d.name),
+        name: descr.name,
+        description: descr.description,
+        protocol: descr.protocol,
+        comment: descr.comment,
+    })
+}
+
+/// Load the results of an assay in concise form: One row per tested substance, with its CID,
+/// activity outcome, and activity value. This can be large for screening assays.
+pub fn get_concise(aid: u32) -> Result<Table, ReqError> {
+    let resp = query(NamespaceAssay::Aid, &[aid], OpSpecAssay::Concise)?;
+    Table::from_json(&resp)
+}
+
+/// Load dose-response curve data for an assay, per substance.
+pub fn get_dose_response(aid: u32) -> Result<Table, ReqError> {
+    let resp = query(NamespaceAssay::Aid, &[aid], OpSpecAssay::DoseResponse)?;
+    Table::from_json(&resp)
+}
+
+/// Load the targets of one or more assays, as the requested identifier types.
+pub fn get_targets(
+    aids: &[u32],
+    target_types: &[AssayTargetType],
+) -> Result<Vec<AssayTargetInfo>, ReqError> {
+    let resp = query(
+        NamespaceAssay::Aid,
+        aids,
+        OpSpecAssay::Targets(target_types.to_vec()),
+    )?;
+    let parsed: AssayTargetResp = serde_json::from_str(&resp)?;
+    Ok(parsed.information_list.information)
+}
+
+/// Find assays (AIDs) that target a protein or gene, e.g. by accession "P00533", or gene
+/// symbol "EGFR".
+pub fn aids_by_target(target_type: AssayTargetType, target: &str) -> Result<Vec<u32>, ReqError> {
+    let resp = url_api_query(
+        Domain::Assay,
+        Namespace::Assay(NamespaceAssay::Target(target_type)),
+        &[target.to_owned()],
+        OperationSpecification::Assay(OpSpecAssay::Aids),
+    )?;
+
+    let parsed: AidListResp = serde_json::from_str(&resp)?;
+    Ok(parsed.identifier_list.aid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubchem::{OpOptions, OutputFormat, api_url};
+
+    #[test]
+    fn target_name_with_spaces() {
+        let url = api_url(
+            Domain::Assay,
+            Namespace::Assay(NamespaceAssay::Target(AssayTargetType::ProteinName)),
+            "Epidermal growth factor receptor",
+            OperationSpecification::Assay(OpSpecAssay::Aids),
+            &OpOptions::default(),
+            OutputFormat::Json,
+        );
+
+        assert_eq!(
+            url.as_str(),
+            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/assay/target/proteinname/Epidermal%20growth%20factor%20receptor/aids/JSON"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Typed access to the PubChem cell line domain, e.g. for filtering assay data by cell line.
+//! [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Cells)
+
+use serde::Deserialize;
+
+use crate::{
+    ReqError,
+    pubchem::{
+        Domain, Namespace, NamespaceCell, OpSpecCell, OperationSpecification, url_api_query,
+    },
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CellSummary {
+    /// E.g. "CVCL_0030".
+    #[serde(rename = "CellAccession")]
+    pub accession: String,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Sex")]
+    pub sex: Option<String>,
+    /// E.g. "Cancer cell line".
+    #[serde(rename = "Category")]
+    pub category: Option<String>,
+    #[serde(rename = "SourceTissue")]
+    pub source_tissue: Option<String>,
+    #[serde(rename = "SourceTaxonomyID")]
+    pub source_taxonomy_id: Option<u32>,
+    #[serde(rename = "SourceOrganism")]
+    pub source_organism: Option<String>,
+    #[serde(rename = "Synonym", default)]
+    pub synonyms: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct CellSummariesInner {
+    #[serde(rename = "CellSummary", default)]
+    cell_summary: Vec<CellSummary>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct CellSummariesResp {
+    #[serde(rename = "CellSummaries")]
+    cell_summaries: CellSummariesInner,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct CellInfo {
+    #[serde(rename = "AID", default)]
+    aid: Vec<u32>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct CellInfoList {
+    #[serde(rename = "Information", default)]
+    information: Vec<CellInfo>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct CellInfoResp {
+    #[serde(rename = "InformationList")]
+    information_list: CellInfoList,
+}
+
+fn query(namespace: NamespaceCell, idents: &[&str], op: OpSpecCell) -> Result<String, ReqError> {
+    let idents: Vec<_> = idents.iter().map(|i| i.to_string()).collect();
+
+    url_api_query(
+        Domain::Cell,
+        Namespace::Cell(namespace),
+        &idents,
+        OperationSpecification::Cell(op),
+    )
+}
+
+/// Load summaries of one or more cell lines, e.g. `get_summaries(Synonym, &["HeLa"])`.
+pub fn get_summaries(
+    namespace: NamespaceCell,
+    idents: &[&str],
+) -> Result<Vec<CellSummary>, ReqError> {
+    let resp = query(namespace, idents, OpSpecCell::Summary)?;
+    let parsed: CellSummariesResp = serde_json::from_str(&resp)?;
+    Ok(parsed.cell_summaries.cell_summary)
+}
+
+/// Find assays (AIDs) performed using a cell line.
+pub fn get_aids(namespace: NamespaceCell, ident: &str) -> Result<Vec<u32>, ReqError> {
+    let resp = query(namespace, &[ident], OpSpecCell::Aids)?;
+    let parsed: CellInfoResp = serde_json::from_str(&resp)?;
+
+    Ok(parsed
+        .information_list
+        .information
+        .into_iter()
+        .flat_map(|i| i.aid)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubchem::{OpOptions, OutputFormat, api_url};
+
+    #[test]
+    fn synonym_with_spaces() {
+        let url = api_url(
+            Domain::Cell,
+            Namespace::Cell(NamespaceCell::Synonym),
+            "HeLa S3",
+            OperationSpecification::Cell(OpSpecCell::Summary),
+            &OpOptions::default(),
+            OutputFormat::Json,
+        );
+
+        assert_eq!(
+            url.as_str(),
+            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/cell/synonym/HeLa%20S3/summary/JSON"
+        );
+    }
+}
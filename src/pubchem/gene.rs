@@ -0,0 +1,152 @@
+//! Typed access to the PubChem gene domain.
+//! [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Genes)
+
+use serde::Deserialize;
+
+use crate::{
+    ReqError,
+    pubchem::{
+        Domain, Namespace, NamespaceGene, OpSpecGene, OperationSpecification, Table, url_api_query,
+    },
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GeneSummary {
+    #[serde(rename = "GeneID")]
+    pub gene_id: u32,
+    #[serde(rename = "Symbol")]
+    pub symbol: Option<String>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "TaxonomyID")]
+    pub taxonomy_id: Option<u32>,
+    /// The organism's scientific name, e.g. "Homo sapiens".
+    #[serde(rename = "Taxonomy")]
+    pub taxonomy: Option<String>,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "Synonym", default)]
+    pub synonyms: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct GeneSummariesInner {
+    #[serde(rename = "GeneSummary", default)]
+    gene_summary: Vec<GeneSummary>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct GeneSummariesResp {
+    #[serde(rename = "GeneSummaries")]
+    gene_summaries: GeneSummariesInner,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct GeneInfo {
+    #[serde(rename = "AID", default)]
+    aid: Vec<u32>,
+    #[serde(rename = "PathwayAccession", default)]
+    pathway_accession: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct GeneInfoList {
+    #[serde(rename = "Information", default)]
+    information: Vec<GeneInfo>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct GeneInfoResp {
+    #[serde(rename = "InformationList")]
+    information_list: GeneInfoList,
+}
+
+fn query(namespace: NamespaceGene, idents: &[String], op: OpSpecGene) -> Result<String, ReqError> {
+    url_api_query(
+        Domain::Gene,
+        Namespace::Gene(namespace),
+        idents,
+        OperationSpecification::Gene(op),
+    )
+}
+
+fn query_info(gene_id: u32, op: OpSpecGene) -> Result<GeneInfo, ReqError> {
+    let resp = query(NamespaceGene::GeneId, &[gene_id.to_string()], op)?;
+    let parsed: GeneInfoResp = serde_json::from_str(&resp)?;
+
+    parsed
+        .information_list
+        .information
+        .into_iter()
+        .next()
+        .ok_or(ReqError::NotFound {
+            ident: gene_id.to_string(),
+        })
+}
+
+/// Load summaries of one or more genes, by NCBI Gene ID.
+pub fn get_summaries(gene_ids: &[u32]) -> Result<Vec<GeneSummary>, ReqError> {
+    let idents: Vec<_> = gene_ids.iter().map(|i| i.to_string()).collect();
+
+    let resp = query(NamespaceGene::GeneId, &idents, OpSpecGene::Summary)?;
+    let parsed: GeneSummariesResp = serde_json::from_str(&resp)?;
+    Ok(parsed.gene_summaries.gene_summary)
+}
+
+/// Load summaries of genes matching a symbol (e.g. "EGFR"), or a synonym.
+pub fn get_summaries_by_name(
+    namespace: NamespaceGene,
+    name: &str,
+) -> Result<Vec<GeneSummary>, ReqError> {
+    let resp = query(namespace, &[name.to_owned()], OpSpecGene::Summary)?;
+    let parsed: GeneSummariesResp = serde_json::from_str(&resp)?;
+    Ok(parsed.gene_summaries.gene_summary)
+}
+
+/// Find assays (AIDs) that target a gene.
+pub fn get_aids(gene_id: u32) -> Result<Vec<u32>, ReqError> {
+    Ok(query_info(gene_id, OpSpecGene::Aids)?.aid)
+}
+
+/// Find pathway accessions a gene participates in, e.g. "Reactome:R-HSA-177929".
+pub fn get_pathways(gene_id: u32) -> Result<Vec<String>, ReqError> {
+    Ok(query_info(gene_id, OpSpecGene::PwAccs)?.pathway_accession)
+}
+
+/// Load bioactivity results for a gene in concise form: One row per assay and tested substance.
+pub fn get_concise(gene_id: u32) -> Result<Table, ReqError> {
+    let resp = query(
+        NamespaceGene::GeneId,
+        &[gene_id.to_string()],
+        OpSpecGene::Concise,
+    )?;
+    Table::from_json(&resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubchem::{OpOptions, OutputFormat, api_url};
+
+    #[test]
+    fn synonym_with_spaces() {
+        let url = api_url(
+            Domain::Gene,
+            Namespace::Gene(NamespaceGene::Synonym),
+            "epidermal growth factor receptor",
+            OperationSpecification::Gene(OpSpecGene::Summary),
+            &OpOptions::default(),
+            OutputFormat::Json,
+        );
+
+        assert_eq!(
+            url.as_str(),
+            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/gene/synonym/epidermal%20growth%20factor%20receptor/summary/JSON"
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Typed access to the PubChem pathway domain, e.g. for enumerating compounds and genes that
+//! participate in a Reactome or KEGG pathway.
+//! [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Pathways)
+
+use serde::Deserialize;
+
+use crate::{
+    ReqError,
+    pubchem::{
+        Domain, Namespace, NamespacePathway, OpSpecPathway, OperationSpecification, url_api_query,
+    },
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PathwaySummary {
+    /// E.g. "Reactome:R-HSA-70171".
+    #[serde(rename = "PathwayAccession")]
+    pub accession: String,
+    #[serde(rename = "SourceName")]
+    pub source_name: Option<String>,
+    #[serde(rename = "SourceID")]
+    pub source_id: Option<String>,
+    #[serde(rename = "SourceURL")]
+    pub source_url: Option<String>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    /// E.g. "organism_specific", "conserved".
+    #[serde(rename = "Type")]
+    pub type_: Option<String>,
+    #[serde(rename = "Category")]
+    pub category: Option<String>,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "TaxonomyID")]
+    pub taxonomy_id: Option<u32>,
+    #[serde(rename = "Taxonomy")]
+    pub taxonomy: Option<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct PathwaySummariesInner {
+    #[serde(rename = "PathwaySummary", default)]
+    pathway_summary: Vec<PathwaySummary>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct PathwaySummariesResp {
+    #[serde(rename = "PathwaySummaries")]
+    pathway_summaries: PathwaySummariesInner,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct PathwayInfo {
+    #[serde(rename = "CID", default)]
+    cid: Vec<u32>,
+    #[serde(rename = "GeneID", default)]
+    gene_id: Vec<u32>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct PathwayInfoList {
+    #[serde(rename = "Information", default)]
+    information: Vec<PathwayInfo>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct PathwayInfoResp {
+    #[serde(rename = "InformationList")]
+    information_list: PathwayInfoList,
+}
+
+fn query(accessions: &[&str], op: OpSpecPathway) -> Result<String, ReqError> {
+    let idents: Vec<_> = accessions.iter().map(|a| a.to_string()).collect();
+
+    url_api_query(
+        Domain::Pathway,
+        Namespace::Pathway(NamespacePathway::PwAcc),
+        &idents,
+        OperationSpecification::Pathway(op),
+    )
+}
+
+fn query_info(accession: &str, op: OpSpecPathway) -> Result<Vec<PathwayInfo>, ReqError> {
+    let resp = query(&[accession], op)?;
+    let parsed: PathwayInfoResp = serde_json::from_str(&resp)?;
+    Ok(parsed.information_list.information)
+}
+
+/// Load summaries of one or more pathways, by accession, e.g. "Reactome:R-HSA-70171".
+pub fn get_summaries(accessions: &[&str]) -> Result<Vec<PathwaySummary>, ReqError> {
+    let resp = query(accessions, OpSpecPathway::Summary)?;
+    let parsed: PathwaySummariesResp = serde_json::from_str(&resp)?;
+    Ok(parsed.pathway_summaries.pathway_summary)
+}
+
+/// Find the compounds (CIDs) that participate in a pathway.
+pub fn get_cids(accession: &str) -> Result<Vec<u32>, ReqError> {
+    let info = query_info(accession, OpSpecPathway::Cids)?;
+    Ok(info.into_iter().flat_map(|i| i.cid).collect())
+}
+
+/// Find the genes (NCBI Gene IDs) that participate in a pathway.
+pub fn get_genes(accession: &str) -> Result<Vec<u32>, ReqError> {
+    let info = query_info(accession, OpSpecPathway::Genes)?;
+    Ok(info.into_iter().flat_map(|i| i.gene_id).collect())
+}
@@ -0,0 +1,130 @@
+//! Typed access to the PubChem protein domain. This supports target-centric queries, e.g. all
+//! assays for a protein accession.
+//! [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Proteins)
+
+use serde::Deserialize;
+
+use crate::{
+    ReqError,
+    pubchem::{
+        Domain, Namespace, NamespaceProtein, OpSpecProtein, OperationSpecification, Table,
+        url_api_query,
+    },
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProteinSummary {
+    #[serde(rename = "ProteinAccession")]
+    pub accession: String,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "TaxonomyID")]
+    pub taxonomy_id: Option<u32>,
+    /// The organism's scientific name, e.g. "Homo sapiens".
+    #[serde(rename = "Taxonomy")]
+    pub taxonomy: Option<String>,
+    #[serde(rename = "Synonym", default)]
+    pub synonyms: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct ProteinSummariesInner {
+    #[serde(rename = "ProteinSummary", default)]
+    protein_summary: Vec<ProteinSummary>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct ProteinSummariesResp {
+    #[serde(rename = "ProteinSummaries")]
+    protein_summaries: ProteinSummariesInner,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct ProteinInfo {
+    #[serde(rename = "AID", default)]
+    aid: Vec<u32>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct ProteinInfoList {
+    #[serde(rename = "Information", default)]
+    information: Vec<ProteinInfo>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct ProteinInfoResp {
+    #[serde(rename = "InformationList")]
+    information_list: ProteinInfoList,
+}
+
+fn query(
+    namespace: NamespaceProtein,
+    idents: &[String],
+    op: OpSpecProtein,
+) -> Result<String, ReqError> {
+    url_api_query(
+        Domain::Protein,
+        Namespace::Protein(namespace),
+        idents,
+        OperationSpecification::Protein(op),
+    )
+}
+
+/// Load summaries of one or more proteins, e.g. by accession: `get_summaries(Accession, &["P00533"])`.
+pub fn get_summaries(
+    namespace: NamespaceProtein,
+    idents: &[&str],
+) -> Result<Vec<ProteinSummary>, ReqError> {
+    let idents: Vec<_> = idents.iter().map(|i| i.to_string()).collect();
+
+    let resp = query(namespace, &idents, OpSpecProtein::Summary)?;
+    let parsed: ProteinSummariesResp = serde_json::from_str(&resp)?;
+    Ok(parsed.protein_summaries.protein_summary)
+}
+
+/// Find assays (AIDs) that target a protein, e.g. `get_aids(Accession, "P00533")`.
+pub fn get_aids(namespace: NamespaceProtein, ident: &str) -> Result<Vec<u32>, ReqError> {
+    let resp = query(namespace, &[ident.to_owned()], OpSpecProtein::Aids)?;
+    let parsed: ProteinInfoResp = serde_json::from_str(&resp)?;
+
+    Ok(parsed
+        .information_list
+        .information
+        .into_iter()
+        .flat_map(|i| i.aid)
+        .collect())
+}
+
+/// Load bioactivity results for a protein in concise form: One row per assay and tested substance.
+pub fn get_concise(namespace: NamespaceProtein, ident: &str) -> Result<Table, ReqError> {
+    let resp = query(namespace, &[ident.to_owned()], OpSpecProtein::Concise)?;
+    Table::from_json(&resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubchem::{OpOptions, OutputFormat, api_url};
+
+    #[test]
+    fn synonym_with_spaces() {
+        let url = api_url(
+            Domain::Protein,
+            Namespace::Protein(NamespaceProtein::Synonym),
+            "Epidermal growth factor receptor",
+            OperationSpecification::Protein(OpSpecProtein::Summary),
+            &OpOptions::default(),
+            OutputFormat::Json,
+        );
+
+        assert_eq!(
+            url.as_str(),
+            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/protein/synonym/Epidermal%20growth%20factor%20receptor/summary/JSON"
+        );
+    }
+}
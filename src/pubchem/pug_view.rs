@@ -0,0 +1,233 @@
+//! [PUG-View API docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-view)
+//!
+//! PUG-View provides the full, annotated compound records shown on PubChem's summary pages.
+//! These are organized as a tree of sections, e.g. "Pharmacology and Biochemistry" >
+//! "Pharmacology", where each section contains information items with source references.
+
+use serde::Deserialize;
+use ureq::Agent;
+use url::Url;
+
+use crate::{
+    ReqError,
+    pubchem::{make_agent_pubchem, make_agent_pubchem_download},
+};
+
+const BASE_URL: &str = "https://pubchem.ncbi.nlm.nih.gov/rest/pug_view/data/compound";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Markup {
+    #[serde(rename = "Start")]
+    pub start: u32,
+    #[serde(rename = "Length")]
+    pub length: u32,
+    #[serde(rename = "URL")]
+    pub url: Option<String>,
+    /// E.g. "PubChem Internal Link", "Icon".
+    #[serde(rename = "Type")]
+    pub type_: Option<String>,
+    #[serde(rename = "Extra")]
+    pub extra: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StringWithMarkup {
+    #[serde(rename = "String")]
+    pub string: String,
+    #[serde(rename = "Markup", default)]
+    pub markup: Vec<Markup>,
+}
+
+/// The value of an information item. Generally, only one of these fields is populated.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct InfoValue {
+    #[serde(rename = "StringWithMarkup", default)]
+    pub string_with_markup: Vec<StringWithMarkup>,
+    #[serde(rename = "Number", default)]
+    pub number: Vec<f64>,
+    /// The unit of `number`, e.g. "g/mol".
+    #[serde(rename = "Unit")]
+    pub unit: Option<String>,
+    #[serde(rename = "Boolean", default)]
+    pub boolean: Vec<bool>,
+    #[serde(rename = "DateISO8601", default)]
+    pub date_iso8601: Vec<String>,
+    #[serde(rename = "ExternalDataURL", default)]
+    pub external_data_url: Vec<String>,
+}
+
+impl InfoValue {
+    /// A text representation of the value, e.g. for display.
+    pub fn to_text(&self) -> String {
+        if !self.string_with_markup.is_empty() {
+            let strs: Vec<_> = self
+                .string_with_markup
+                .iter()
+                .map(|s| s.string.as_str())
+                .collect();
+            return strs.join("\n");
+        }
+
+        if !self.number.is_empty() {
+            let nums: Vec<_> = self.number.iter().map(|n| n.to_string()).collect();
+            return match &self.unit {
+                Some(unit) => format!("{} {unit}", nums.join(", ")),
+                None => nums.join(", "),
+            };
+        }
+
+        if !self.boolean.is_empty() {
+            let v: Vec<_> = self.boolean.iter().map(|b| b.to_string()).collect();
+            return v.join(", ");
+        }
+
+        if !self.date_iso8601.is_empty() {
+            return self.date_iso8601.join(", ");
+        }
+
+        self.external_data_url.join(", ")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Information {
+    /// Indexes into the record's references.
+    #[serde(rename = "ReferenceNumber")]
+    pub reference_number: Option<u32>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "Value", default)]
+    pub value: InfoValue,
+    #[serde(rename = "URL")]
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Section {
+    /// The heading, e.g. "Solubility".
+    #[serde(rename = "TOCHeading")]
+    pub toc_heading: String,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "Section", default)]
+    pub sections: Vec<Section>,
+    #[serde(rename = "Information", default)]
+    pub information: Vec<Information>,
+}
+
+impl Section {
+    /// Find a section by heading, searching this section and its descendants, depth-first.
+    /// Case-insensitive.
+    pub fn find(&self, heading: &str) -> Option<&Section> {
+        if self.toc_heading.eq_ignore_ascii_case(heading) {
+            return Some(self);
+        }
+        self.sections.iter().find_map(|s| s.find(heading))
+    }
+
+    /// Text of all information items in this section and its descendants.
+    pub fn texts(&self) -> Vec<String> {
+        let mut result: Vec<_> = self.information.iter().map(|i| i.value.to_text()).collect();
+        for section in &self.sections {
+            result.extend(section.texts());
+        }
+        result
+    }
+}
+
+/// A source that information items are attributed to.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Reference {
+    #[serde(rename = "ReferenceNumber")]
+    pub reference_number: u32,
+    /// E.g. "DrugBank", "Hazardous Substances Data Bank (HSDB)".
+    #[serde(rename = "SourceName")]
+    pub source_name: String,
+    #[serde(rename = "SourceID")]
+    pub source_id: Option<String>,
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "URL")]
+    pub url: Option<String>,
+    #[serde(rename = "LicenseNote")]
+    pub license_note: Option<String>,
+    #[serde(rename = "LicenseURL")]
+    pub license_url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Record {
+    /// E.g. "CID".
+    #[serde(rename = "RecordType")]
+    pub record_type: String,
+    #[serde(rename = "RecordNumber")]
+    pub record_number: u32,
+    #[serde(rename = "RecordTitle")]
+    pub record_title: Option<String>,
+    #[serde(rename = "Section", default)]
+    pub sections: Vec<Section>,
+    #[serde(rename = "Reference", default)]
+    pub references: Vec<Reference>,
+}
+
+impl Record {
+    /// Find a section by heading, e.g. "Pharmacology" or "Solubility", at any depth.
+    /// Case-insensitive.
+    pub fn find_section(&self, heading: &str) -> Option<&Section> {
+        self.sections.iter().find_map(|s| s.find(heading))
+    }
+
+    pub fn reference(&self, reference_number: u32) -> Option<&Reference> {
+        self.references
+            .iter()
+            .find(|r| r.reference_number == reference_number)
+    }
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct RecordResp {
+    #[serde(rename = "Record")]
+    record: Record,
+}
+
+/// `ident` is used in the error if the record, or section isn't present.
+fn load(agent: Agent, url: &Url, ident: &str) -> Result<Record, ReqError> {
+    let resp = agent.get(url.as_str()).call()?;
+    // PubChem returns a `PUGVIEW.NotFound` fault for unknown CIDs, and for sections a record
+    // doesn't have.
+    if resp.status() == 404 {
+        return Err(ReqError::NotFound {
+            ident: ident.to_owned(),
+        });
+    }
+    if resp.status() != 200 {
+        return Err(ReqError::Http);
+    }
+
+    // Full records can exceed the default body size limit, so we stream without one.
+    let parsed: RecordResp = serde_json::from_reader(resp.into_body().into_reader())?;
+    Ok(parsed.record)
+}
+
+/// Load the full annotated record of a compound. Note that these can be large, for well-known
+/// compounds; consider [`load_section`] if you only need part of it.
+pub fn load_record(cid: u32) -> Result<Record, ReqError> {
+    let url = Url::parse(&format!("{BASE_URL}/{cid}/JSON")).unwrap();
+    // These can be large and slow to generate, so we don't apply the usual overall timeout.
+    load(make_agent_pubchem_download(), &url, &cid.to_string())
+}
+
+/// Load only a named section of a compound's record, e.g. "Pharmacology" or "Solubility". The
+/// returned record contains the section, nested under its parent sections; use
+/// [`Record::find_section`] to access it.
+pub fn load_section(cid: u32, heading: &str) -> Result<Record, ReqError> {
+    let mut url = Url::parse(&format!("{BASE_URL}/{cid}/JSON")).unwrap();
+    url.query_pairs_mut().append_pair("heading", heading);
+
+    load(make_agent_pubchem(), &url, &format!("{cid}: {heading}"))
+}
@@ -0,0 +1,151 @@
+//! Typed access to the PubChem taxonomy domain, e.g. for resolving organisms referenced by
+//! other PubChem records.
+//! [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/pug-rest#section=Taxonomies)
+
+use serde::Deserialize;
+
+use crate::{
+    ReqError,
+    pubchem::{
+        Domain, Namespace, NamespaceTaxonomy, OpSpecTaxonomy, OperationSpecification, url_api_query,
+    },
+};
+
+/// Ranks from the organism up, where available.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RankedLineage {
+    pub species: Option<String>,
+    pub genus: Option<String>,
+    pub family: Option<String>,
+    pub order: Option<String>,
+    pub class: Option<String>,
+    pub phylum: Option<String>,
+    pub kingdom: Option<String>,
+    pub superkingdom: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TaxonomySummary {
+    #[serde(rename = "TaxonomyID")]
+    pub taxonomy_id: u32,
+    #[serde(rename = "ScientificName")]
+    pub scientific_name: Option<String>,
+    #[serde(rename = "CommonName")]
+    pub common_name: Option<String>,
+    /// E.g. "species", "genus".
+    #[serde(rename = "Rank")]
+    pub rank: Option<String>,
+    #[serde(rename = "RankedLineage", default)]
+    pub ranked_lineage: RankedLineage,
+    #[serde(rename = "Synonym", default)]
+    pub synonyms: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TaxonomySummariesInner {
+    #[serde(rename = "TaxonomySummary", default)]
+    taxonomy_summary: Vec<TaxonomySummary>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TaxonomySummariesResp {
+    #[serde(rename = "TaxonomySummaries")]
+    taxonomy_summaries: TaxonomySummariesInner,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TaxonomyInfo {
+    #[serde(rename = "AID", default)]
+    aid: Vec<u32>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TaxonomyInfoList {
+    #[serde(rename = "Information", default)]
+    information: Vec<TaxonomyInfo>,
+}
+
+/// Deserializing only
+#[derive(Deserialize)]
+struct TaxonomyInfoResp {
+    #[serde(rename = "InformationList")]
+    information_list: TaxonomyInfoList,
+}
+
+fn query(
+    namespace: NamespaceTaxonomy,
+    idents: &[String],
+    op: OpSpecTaxonomy,
+) -> Result<String, ReqError> {
+    url_api_query(
+        Domain::Taxonomy,
+        Namespace::Taxonomy(namespace),
+        idents,
+        OperationSpecification::Taxonomy(op),
+    )
+}
+
+/// Load summaries of one or more organisms, by NCBI Taxonomy ID, e.g. 9606 for humans.
+pub fn get_summaries(tax_ids: &[u32]) -> Result<Vec<TaxonomySummary>, ReqError> {
+    let idents: Vec<_> = tax_ids.iter().map(|i| i.to_string()).collect();
+
+    let resp = query(NamespaceTaxonomy::TaxId, &idents, OpSpecTaxonomy::Summary)?;
+    let parsed: TaxonomySummariesResp = serde_json::from_str(&resp)?;
+    Ok(parsed.taxonomy_summaries.taxonomy_summary)
+}
+
+/// Resolve an organism by name, e.g. "Escherichia coli", or "human".
+pub fn get_summaries_by_name(name: &str) -> Result<Vec<TaxonomySummary>, ReqError> {
+    let resp = query(
+        NamespaceTaxonomy::Synonym,
+        &[name.to_owned()],
+        OpSpecTaxonomy::Summary,
+    )?;
+    let parsed: TaxonomySummariesResp = serde_json::from_str(&resp)?;
+    Ok(parsed.taxonomy_summaries.taxonomy_summary)
+}
+
+/// Find assays (AIDs) involving an organism.
+pub fn get_aids(tax_id: u32) -> Result<Vec<u32>, ReqError> {
+    let resp = query(
+        NamespaceTaxonomy::TaxId,
+        &[tax_id.to_string()],
+        OpSpecTaxonomy::Aids,
+    )?;
+    let parsed: TaxonomyInfoResp = serde_json::from_str(&resp)?;
+
+    Ok(parsed
+        .information_list
+        .information
+        .into_iter()
+        .flat_map(|i| i.aid)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubchem::{OpOptions, OutputFormat, api_url};
+
+    #[test]
+    fn name_with_spaces() {
+        let url = api_url(
+            Domain::Taxonomy,
+            Namespace::Taxonomy(NamespaceTaxonomy::Synonym),
+            "Escherichia coli",
+            OperationSpecification::Taxonomy(OpSpecTaxonomy::Summary),
+            &OpOptions::default(),
+            OutputFormat::Json,
+        );
+
+        assert_eq!(
+            url.as_str(),
+            "https://pubchem.ncbi.nlm.nih.gov/rest/pug/taxonomy/synonym/Escherichia%20coli/summary/JSON"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Client-side request throttling for PubChem. PubChem limits clients to 5 requests per second,
+//! and reports its current load in the `X-Throttling-Control` header of each response. We space
+//! out requests accordingly, slowing down when the service reports moderate or heavy load, to
+//! avoid being blocked during batch jobs.
+//! [Docs](https://pubchem.ncbi.nlm.nih.gov/docs/dynamic-request-throttling)
+
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use ureq::{
+    Body, SendBody,
+    http::{HeaderMap, Request, Response},
+    middleware::MiddlewareNext,
+};
+
+const HEADER: &str = "X-Throttling-Control";
+
+/// PubChem's policy: No more than 5 requests per second.
+const MIN_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Load status, as reported by PubChem.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ThrottleLevel {
+    /// Light load.
+    Green,
+    /// Moderate load.
+    Yellow,
+    /// Heavy load; requests should be reduced.
+    Red,
+    /// Over the limit; requests are being blocked.
+    Black,
+}
+
+impl ThrottleLevel {
+    fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_ref() {
+            "green" => Some(Self::Green),
+            "yellow" => Some(Self::Yellow),
+            "red" => Some(Self::Red),
+            "black" => Some(Self::Black),
+            _ => None,
+        }
+    }
+
+    /// The minimum time between requests at this level.
+    fn interval(self) -> Duration {
+        match self {
+            Self::Green => MIN_INTERVAL,
+            Self::Yellow => Duration::from_secs(1),
+            Self::Red => Duration::from_secs(5),
+            Self::Black => Duration::from_secs(60),
+        }
+    }
+}
+
+/// The status from the most recent response. Each is reported with the percentage of the
+/// limit used, e.g. "Request Count status: Green (0%)".
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ThrottleStatus {
+    /// The number of requests per time period.
+    pub request_count: ThrottleLevel,
+    /// The running time of requests per time period.
+    pub request_time: ThrottleLevel,
+    /// The load on the service overall.
+    pub service: ThrottleLevel,
+}
+
+impl ThrottleStatus {
+    /// Parse the header value, e.g. "Request Count status: Green (0%), Request Time status:
+    /// Green (0%), Service status: Green (20%)".
+    pub fn from_header(header: &str) -> Option<Self> {
+        let mut request_count = None;
+        let mut request_time = None;
+        let mut service = None;
+
+        for part in header.split(',') {
+            let Some((name, value)) = part.split_once(':') else {
+                continue;
+            };
+            // Remove the percentage.
+            let level = ThrottleLevel::from_str(value.split('(').next().unwrap_or_default());
+
+            match name.trim().to_lowercase().as_ref() {
+                "request count status" => request_count = level,
+                "request time status" => request_time = level,
+                "service status" => service = level,
+                _ => (),
+            }
+        }
+
+        Some(Self {
+            request_count: request_count?,
+            request_time: request_time?,
+            service: service?,
+        })
+    }
+
+    /// The most severe of the three levels.
+    pub fn worst(&self) -> ThrottleLevel {
+        self.request_count.max(self.request_time).max(self.service)
+    }
+}
+
+struct State {
+    /// When the most recent request was made, or is scheduled to be made.
+    last_request: Option<Instant>,
+    status: Option<ThrottleStatus>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    last_request: None,
+    status: None,
+});
+
+/// The status reported by the most recent PubChem response, if there has been one.
+pub fn status() -> Option<ThrottleStatus> {
+    STATE.lock().unwrap().status
+}
+
+/// Block until we can make the next request. We reserve a time slot under the lock, then sleep
+/// after releasing it, so concurrent requests are spaced out, without blocking status updates.
+fn wait_turn() {
+    let scheduled = {
+        let mut state = STATE.lock().unwrap();
+
+        let interval = state
+            .status
+            .map(|s| s.worst().interval())
+            .unwrap_or(MIN_INTERVAL);
+
+        let now = Instant::now();
+        let scheduled = match state.last_request {
+            Some(last) => (last + interval).max(now),
+            None => now,
+        };
+
+        state.last_request = Some(scheduled);
+        scheduled
+    };
+
+    let now = Instant::now();
+    if scheduled > now {
+        thread::sleep(scheduled - now);
+    }
+}
+
+fn update(headers: &HeaderMap) {
+    let status = headers
+        .get(HEADER)
+        .and_then(|h| h.to_str().ok())
+        .and_then(ThrottleStatus::from_header);
+
+    if let Some(status) = status {
+        STATE.lock().unwrap().status = Some(status);
+    }
+}
+
+/// Middleware for PubChem agents, applying the throttle to each request.
+pub(crate) fn middleware(
+    req: Request<SendBody>,
+    next: MiddlewareNext,
+) -> Result<Response<Body>, ureq::Error> {
+    wait_turn();
+
+    let resp = next.handle(req)?;
+    update(resp.headers());
+
+    Ok(resp)
+}
@@ -6,8 +6,11 @@
 //! PDB Data API: https://data.rcsb.org/#data-api
 
 use std::{
+    collections::HashMap,
+    fs::File,
     io,
-    io::{ErrorKind, Read},
+    io::{BufRead, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
 };
 
 #[cfg(feature = "encode")]
@@ -16,7 +19,9 @@ use flate2::read::GzDecoder;
 // todo: Determine if you want this.
 use na_seq::{AminoAcid, seq_aa_to_str};
 use rand::{self, Rng};
-use serde::{Deserialize, Serialize, Serializer};
+#[cfg(feature = "chrono")]
+use serde::Deserializer;
+use serde::{Deserialize, Serialize, Serializer, de::DeserializeOwned, ser::SerializeMap};
 use serde_aux::prelude::*;
 use serde_json::{self};
 use ureq::{
@@ -24,7 +29,7 @@ use ureq::{
     http::{Response, StatusCode},
 };
 
-use crate::{ReqError, make_agent};
+use crate::{ReqError, make_agent, make_agent_download, pdbe};
 
 const BASE_URL: &str = "https://www.rcsb.org/structure";
 
@@ -33,29 +38,64 @@ const STRUCTURE_FILE_URL: &str = "https://files.rcsb.org/view";
 
 const SEARCH_API_URL: &str = "https://search.rcsb.org/rcsbsearch/v2/query";
 const DATA_API_URL: &str = "https://data.rcsb.org/rest/v1/core/entry";
+const DATA_API_CORE_URL: &str = "https://data.rcsb.org/rest/v1/core";
+
+const CCD_URL: &str = "https://files.wwpdb.org/pub/pdb/data/monomers/components.cif.gz";
+const PRD_URL: &str = "https://files.wwpdb.org/pub/pdb/data/bird/prd/prd-all.cif.gz";
 
 // An arbitrary limit to prevent excessive queries to the PDB data api,
 // and to simplify display code.
 const MAX_RESULTS: usize = 8;
 
+/// The value of a terminal search node. Most operators take a single value; `range` takes bounds.
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum SearchValue {
+    Str(String),
+    Num(f64),
+    Range(SearchRange),
+}
+
+impl From<&str> for SearchValue {
+    fn from(v: &str) -> Self {
+        Self::Str(v.to_owned())
+    }
+}
+
+impl From<f64> for SearchValue {
+    fn from(v: f64) -> Self {
+        Self::Num(v)
+    }
+}
+
+/// Bounds for the `range` operator. Dates can be absolute (e.g. "2024-01-31"), or relative
+/// (e.g. "now-1w", "now-3M", "now").
+#[derive(Clone, Debug, Serialize)]
+pub struct SearchRange {
+    pub from: String,
+    pub to: String,
+    pub include_lower: bool,
+    pub include_upper: bool,
+}
+
 #[derive(Default, Serialize)]
 pub struct PdbSearchParams {
     #[serde(skip_serializing_if = "Option::is_none")]
-    value: Option<String>,
+    pub value: Option<SearchValue>,
     /// "protein". Not sure what other values are authorized.
     #[serde(skip_serializing_if = "Option::is_none")]
-    sequence_type: Option<String>,
+    pub sequence_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    evalue_cutoff: Option<u8>,
+    pub evalue_cutoff: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    identity_cutoff: Option<f32>,
+    pub identity_cutoff: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    operator: Option<Operator>,
+    pub operator: Option<Operator>,
     #[serde(skip_serializing_if = "Option::is_none")]
     ///https://search.rcsb.org/structure-search-attributes.html
-    attribute: Option<String>,
+    pub attribute: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pattern: Option<String>,
+    pub pattern: Option<String>,
 }
 // "greater", "exact_match", "in", "range", etc. (todo: enum)
 
@@ -183,13 +223,109 @@ impl Serialize for Service {
     }
 }
 
-#[derive(Default, Serialize)]
+#[derive(Clone, Copy, Default)]
+pub enum LogicalOperator {
+    #[default]
+    And,
+    Or,
+}
+
+impl Serialize for LogicalOperator {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let str = match self {
+            Self::And => "and",
+            Self::Or => "or",
+        };
+
+        serializer.serialize_str(str)
+    }
+}
+
+/// A node in the search query tree. Terminal nodes use `service` and `parameters`; group nodes
+/// use `logical_operator` and `nodes`.
+/// https://search.rcsb.org/#search-api
+#[derive(Default)]
 pub struct PdbSearchQuery {
     /// "terminal", or "group"
-    #[serde(rename = "type")]
     pub type_: RcsbType,
     pub service: Service,
     pub parameters: PdbSearchParams,
+    pub logical_operator: LogicalOperator,
+    pub nodes: Vec<PdbSearchQuery>,
+}
+
+impl Serialize for PdbSearchQuery {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("type", &self.type_)?;
+
+        match self.type_ {
+            RcsbType::Terminal => {
+                map.serialize_entry("service", &self.service)?;
+                map.serialize_entry("parameters", &self.parameters)?;
+            }
+            RcsbType::Group => {
+                map.serialize_entry("logical_operator", &self.logical_operator)?;
+                map.serialize_entry("nodes", &self.nodes)?;
+            }
+        }
+
+        map.end()
+    }
+}
+
+impl PdbSearchQuery {
+    /// A terminal node using the text (attribute) service.
+    /// [Attributes](https://search.rcsb.org/structure-search-attributes.html)
+    pub fn text_attr(attribute: &str, operator: Operator, value: &str) -> Self {
+        Self {
+            type_: RcsbType::Terminal,
+            service: Service::Text,
+            parameters: PdbSearchParams {
+                attribute: Some(attribute.to_owned()),
+                operator: Some(operator),
+                value: Some(SearchValue::Str(value.to_owned())),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// A terminal text node using the `range` operator, inclusive of both bounds.
+    pub fn text_range(attribute: &str, from: &str, to: &str) -> Self {
+        Self {
+            type_: RcsbType::Terminal,
+            service: Service::Text,
+            parameters: PdbSearchParams {
+                attribute: Some(attribute.to_owned()),
+                operator: Some(Operator::Range),
+                value: Some(SearchValue::Range(SearchRange {
+                    from: from.to_owned(),
+                    to: to.to_owned(),
+                    include_lower: true,
+                    include_upper: true,
+                })),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// A group node, combining child nodes with a logical operator.
+    pub fn group(logical_operator: LogicalOperator, nodes: Vec<Self>) -> Self {
+        Self {
+            type_: RcsbType::Group,
+            logical_operator,
+            nodes,
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Default, Serialize)]
@@ -209,7 +345,112 @@ pub struct SearchRequestOptions {
     pub scoring_strategy: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sort: Option<Vec<Sort>>,
-    // todo: Paginate
+    /// If true, return all hits, instead of the first page. (The default page is 10 hits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_all_hits: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paginate: Option<Paginate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results_verbosity: Option<ResultsVerbosity>,
+    /// Group hits, e.g. by sequence identity cluster. Requires a compatible return type; e.g.
+    /// `PolymerEntity` for sequence identity, and `Entry` for deposit groups.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_by: Option<GroupBy>,
+    /// Required if `group_by` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_by_return_type: Option<GroupByReturnType>,
+}
+
+/// https://search.rcsb.org/#group-by-return-type
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AggregationMethod {
+    /// Group polymer entities by sequence identity clusters. Set `similarity_cutoff`.
+    SequenceIdentity,
+    /// Group entries deposited together as a group, e.g. fragment screening campaigns.
+    MatchingDepositGroupId,
+    /// Group polymer entities by their UniProt accession.
+    MatchingUniprotAccession,
+}
+
+impl Serialize for AggregationMethod {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let str = match self {
+            Self::SequenceIdentity => "sequence_identity",
+            Self::MatchingDepositGroupId => "matching_deposit_group_id",
+            Self::MatchingUniprotAccession => "matching_uniprot_accession",
+        };
+
+        serializer.serialize_str(str)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct GroupBy {
+    pub aggregation_method: AggregationMethod,
+    /// For sequence identity: 100, 95, 90, 70, 50, or 30 (percent).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity_cutoff: Option<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GroupByReturnType {
+    /// One hit (the representative) per group, in `result_set`.
+    #[default]
+    Representatives,
+    /// All hits, organized by group, in `group_set`.
+    Groups,
+}
+
+impl Serialize for GroupByReturnType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let str = match self {
+            Self::Representatives => "representatives",
+            Self::Groups => "groups",
+        };
+
+        serializer.serialize_str(str)
+    }
+}
+
+/// https://search.rcsb.org/#results_verbosity
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ResultsVerbosity {
+    /// Identifiers only; no scores.
+    Compact,
+    /// Identifiers and scores.
+    #[default]
+    Minimal,
+    /// Identifiers, scores, and the services and match context (e.g. sequence alignments) of
+    /// each hit.
+    Verbose,
+}
+
+impl Serialize for ResultsVerbosity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let str = match self {
+            Self::Compact => "compact",
+            Self::Minimal => "minimal",
+            Self::Verbose => "verbose",
+        };
+
+        serializer.serialize_str(str)
+    }
+}
+
+/// Select a page of results. `start` is the 0-based index of the first hit.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Paginate {
+    pub start: u32,
+    pub rows: u32,
 }
 
 #[derive(Default, Serialize)]
@@ -222,10 +463,233 @@ pub struct PdbPayloadSearch {
     pub request_info: Option<String>,
 }
 
+/// A fluent builder for search payloads, for use with [`search`]. Example:
+///
+/// ```ignore
+/// let payload = SearchBuilder::text()
+///     .attribute("rcsb_entry_info.resolution_combined")
+///     .operator(Operator::Less)
+///     .value(1.5)
+///     .limit(50)
+///     .build();
+///
+/// let results = rcsb::search(&payload)?;
+/// ```
+#[derive(Default)]
+pub struct SearchBuilder {
+    payload: PdbPayloadSearch,
+}
+
+impl SearchBuilder {
+    /// A terminal query using a given service.
+    pub fn new(service: Service) -> Self {
+        let mut result = Self::default();
+        result.payload.query.service = service;
+        result
+    }
+
+    /// A terminal query using the text (attribute) service.
+    pub fn text() -> Self {
+        Self::new(Service::Text)
+    }
+
+    /// A terminal query using the sequence service, for a protein sequence in one-letter codes.
+    pub fn sequence(seq: &str) -> Self {
+        let mut result = Self::new(Service::Sequence);
+        result.payload.query.parameters.value = Some(SearchValue::Str(seq.to_owned()));
+        result.payload.query.parameters.sequence_type = Some("protein".to_owned());
+        result
+    }
+
+    /// Use a custom query tree, e.g. one built with [`PdbSearchQuery::group`].
+    pub fn query(query: PdbSearchQuery) -> Self {
+        Self {
+            payload: PdbPayloadSearch {
+                query,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// [Attributes](https://search.rcsb.org/structure-search-attributes.html)
+    pub fn attribute(mut self, attribute: &str) -> Self {
+        self.payload.query.parameters.attribute = Some(attribute.to_owned());
+        self
+    }
+
+    pub fn operator(mut self, operator: Operator) -> Self {
+        self.payload.query.parameters.operator = Some(operator);
+        self
+    }
+
+    pub fn value(mut self, value: impl Into<SearchValue>) -> Self {
+        self.payload.query.parameters.value = Some(value.into());
+        self
+    }
+
+    /// For sequence searches. 0 - 1.
+    pub fn identity_cutoff(mut self, cutoff: f32) -> Self {
+        self.payload.query.parameters.identity_cutoff = Some(cutoff);
+        self
+    }
+
+    /// For sequence searches.
+    pub fn evalue_cutoff(mut self, cutoff: u8) -> Self {
+        self.payload.query.parameters.evalue_cutoff = Some(cutoff);
+        self
+    }
+
+    pub fn return_type(mut self, return_type: ReturnType) -> Self {
+        self.payload.return_type = return_type;
+        self
+    }
+
+    /// Return at most this many hits.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.request_options().paginate = Some(Paginate {
+            start: 0,
+            rows: limit,
+        });
+        self
+    }
+
+    /// Return all hits, instead of the first page.
+    pub fn all_hits(mut self) -> Self {
+        self.request_options().return_all_hits = Some(true);
+        self
+    }
+
+    pub fn verbosity(mut self, verbosity: ResultsVerbosity) -> Self {
+        self.request_options().results_verbosity = Some(verbosity);
+        self
+    }
+
+    /// Group hits. E.g. for one hit per 90% sequence identity cluster, use a return type of
+    /// `PolymerEntity`, `AggregationMethod::SequenceIdentity`, a similarity cutoff of 90, and
+    /// `GroupByReturnType::Representatives`.
+    pub fn group_by(mut self, group_by: GroupBy, return_type: GroupByReturnType) -> Self {
+        let options = self.request_options();
+        options.group_by = Some(group_by);
+        options.group_by_return_type = Some(return_type);
+        self
+    }
+
+    pub fn build(self) -> PdbPayloadSearch {
+        self.payload
+    }
+
+    fn request_options(&mut self) -> &mut SearchRequestOptions {
+        self.payload
+            .request_options
+            .get_or_insert_with(Default::default)
+    }
+}
+
+/// Match context for a sequence search hit: The alignment between query and subject. Sequence
+/// positions are 1-based.
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct SequenceMatchContext {
+    pub sequence_identity: Option<f32>,
+    pub evalue: Option<f64>,
+    pub bitscore: Option<f32>,
+    pub alignment_length: Option<u32>,
+    pub mismatches: Option<u32>,
+    pub gaps_opened: Option<u32>,
+    pub query_beg: Option<u32>,
+    pub query_end: Option<u32>,
+    pub subject_beg: Option<u32>,
+    pub subject_end: Option<u32>,
+    pub query_length: Option<u32>,
+    pub subject_length: Option<u32>,
+    pub query_aligned_seq: Option<String>,
+    pub subject_aligned_seq: Option<String>,
+}
+
+impl SequenceMatchContext {
+    /// The fraction of the query sequence covered by the alignment, 0 - 1.
+    pub fn query_coverage(&self) -> Option<f32> {
+        let len = self.query_length?;
+        if len == 0 {
+            return None;
+        }
+        let aligned = self.query_end? + 1 - self.query_beg?;
+        Some(aligned as f32 / len as f32)
+    }
+}
+
+/// The scores of a hit for one node of the query.
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct NodeMatch {
+    pub node_id: u32,
+    pub original_score: Option<f32>,
+    pub norm_score: Option<f32>,
+    /// Present for some services, e.g. sequence.
+    #[serde(default)]
+    pub match_context: Vec<SequenceMatchContext>,
+}
+
+/// A service that matched a hit. Only returned with [`ResultsVerbosity::Verbose`].
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct ServiceMatch {
+    /// E.g. "text", or "sequence".
+    pub service_type: String,
+    #[serde(default)]
+    pub nodes: Vec<NodeMatch>,
+}
+
+/// Hits are plain identifiers with compact verbosity; objects otherwise.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PdbSearchResultRaw {
+    Compact(String),
+    Full {
+        identifier: String,
+        #[serde(default)]
+        score: f32,
+        #[serde(default)]
+        services: Vec<ServiceMatch>,
+    },
+}
+
 #[derive(Default, Debug, Deserialize)]
+#[serde(from = "PdbSearchResultRaw")]
 pub struct PdbSearchResult {
     pub identifier: String,
     pub score: f32,
+    /// Only populated with [`ResultsVerbosity::Verbose`].
+    pub services: Vec<ServiceMatch>,
+}
+
+impl From<PdbSearchResultRaw> for PdbSearchResult {
+    fn from(raw: PdbSearchResultRaw) -> Self {
+        match raw {
+            PdbSearchResultRaw::Compact(identifier) => Self {
+                identifier,
+                ..Default::default()
+            },
+            PdbSearchResultRaw::Full {
+                identifier,
+                score,
+                services,
+            } => Self {
+                identifier,
+                score,
+                services,
+            },
+        }
+    }
+}
+
+impl PdbSearchResult {
+    /// Sequence alignments for this hit, if it was matched by a sequence search with
+    /// [`ResultsVerbosity::Verbose`].
+    pub fn sequence_matches(&self) -> impl Iterator<Item = &SequenceMatchContext> {
+        self.services
+            .iter()
+            .filter(|s| s.service_type == "sequence")
+            .flat_map(|s| &s.nodes)
+            .flat_map(|n| &n.match_context)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -234,12 +698,30 @@ pub struct PdbMetaData {
     pub prim_cit_title: String,
 }
 
+/// A group of hits, when using [`GroupByReturnType::Groups`].
+#[derive(Default, Debug, Deserialize)]
+pub struct PdbSearchGroup {
+    /// E.g. the sequence cluster, or deposit group ID.
+    pub identifier: String,
+    #[serde(default)]
+    pub score: f32,
+    #[serde(default)]
+    pub result_set: Vec<PdbSearchResult>,
+}
+
 #[derive(Default, Debug, Deserialize)]
 pub struct PdbSearchResults {
     pub query_id: String,
     pub result_type: String,
     pub total_count: u32,
+    /// Empty when using [`GroupByReturnType::Groups`]; see `group_set`.
+    #[serde(default)]
     pub result_set: Vec<PdbSearchResult>,
+    /// The number of groups, when using `group_by`.
+    pub group_by_count: Option<u32>,
+    /// Populated when using [`GroupByReturnType::Groups`].
+    #[serde(default)]
+    pub group_set: Vec<PdbSearchGroup>,
 }
 
 #[derive(Clone, Default, PartialEq, Debug, Deserialize)]
@@ -292,13 +774,54 @@ pub struct Citation {
     pub year: Option<u16>,
 }
 
+/// A date from the RCSB APIs. With the `chrono` feature, this is parsed into a `DateTime<Utc>`.
+/// Otherwise, it's the string as returned by the API, e.g. "2008-02-19T00:00:00+0000".
+#[cfg(feature = "chrono")]
+pub type RcsbDate = chrono::DateTime<chrono::Utc>;
+#[cfg(not(feature = "chrono"))]
+pub type RcsbDate = String;
+
+/// Parse dates in the format RCSB uses, e.g. "2008-02-19T00:00:00+0000". Accepts plain dates
+/// too, e.g. "2008-02-19".
+#[cfg(feature = "chrono")]
+pub fn parse_date(date: &str) -> Option<RcsbDate> {
+    use chrono::{DateTime, NaiveDate};
+
+    if let Ok(v) = DateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S%z") {
+        return Some(v.to_utc());
+    }
+
+    let date = NaiveDate::parse_from_str(date.get(0..10)?, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+#[cfg(feature = "chrono")]
+fn deser_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RcsbDate, D::Error> {
+    let v = String::deserialize(deserializer)?;
+    parse_date(&v).ok_or_else(|| serde::de::Error::custom(format!("Invalid date: {v}")))
+}
+
+#[cfg(feature = "chrono")]
+fn deser_date_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<RcsbDate>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(v) => parse_date(&v)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("Invalid date: {v}"))),
+        None => Ok(None),
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Deserialize)]
 #[cfg_attr(feature = "encode", derive(Encode, Decode))]
 pub struct PdbxDatabaseStatus {
     pub deposit_site: Option<String>,
     pub pdb_format_compatible: String,
     pub process_site: String,
-    pub recvd_initial_deposition_date: String, // todo: Chrono time
+    #[cfg_attr(feature = "chrono", serde(deserialize_with = "deser_date"))]
+    #[cfg_attr(all(feature = "chrono", feature = "encode"), bincode(with_serde))]
+    pub recvd_initial_deposition_date: RcsbDate,
     pub status_code: String,
     pub status_code_sf: Option<String>,
     pub sgentry: Option<String>,
@@ -347,6 +870,37 @@ pub struct RcsbEntryInfo {
     pub polymer_monomer_count_minimum: u32,
 }
 
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+#[cfg_attr(feature = "encode", derive(Encode, Decode))]
+pub struct RcsbAccessionInfo {
+    #[cfg_attr(feature = "chrono", serde(deserialize_with = "deser_date"))]
+    #[cfg_attr(all(feature = "chrono", feature = "encode"), bincode(with_serde))]
+    pub deposit_date: RcsbDate,
+    #[cfg_attr(feature = "chrono", serde(deserialize_with = "deser_date"))]
+    #[cfg_attr(all(feature = "chrono", feature = "encode"), bincode(with_serde))]
+    pub initial_release_date: RcsbDate,
+    #[serde(default)]
+    #[cfg_attr(feature = "chrono", serde(deserialize_with = "deser_date_opt"))]
+    #[cfg_attr(all(feature = "chrono", feature = "encode"), bincode(with_serde))]
+    pub revision_date: Option<RcsbDate>,
+    pub major_revision: Option<u32>,
+    pub minor_revision: Option<u32>,
+    /// E.g. "REL".
+    pub status_code: Option<String>,
+}
+
+/// IDs of the entities and assemblies in an entry. These are used to query the entity and
+/// assembly endpoints of the data API.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+#[cfg_attr(feature = "encode", derive(Encode, Decode))]
+pub struct RcsbEntryContainerIdentifiers {
+    pub entry_id: String,
+    pub assembly_ids: Option<Vec<String>>,
+    pub polymer_entity_ids: Option<Vec<String>>,
+    pub non_polymer_entity_ids: Option<Vec<String>>,
+    pub branched_entity_ids: Option<Vec<String>>,
+}
+
 /// Top-level struct for results from the RCSB data API.
 /// todo: Fill out fields A/R.
 #[derive(Clone, Default, PartialEq, Debug, Deserialize)]
@@ -359,6 +913,132 @@ pub struct PdbDataResults {
     pub citation: Vec<Citation>,
     pub pdbx_database_status: PdbxDatabaseStatus,
     pub rcsb_entry_info: RcsbEntryInfo,
+    #[serde(default)]
+    pub rcsb_entry_container_identifiers: RcsbEntryContainerIdentifiers,
+    pub rcsb_accession_info: Option<RcsbAccessionInfo>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct EntityBranch {
+    /// E.g. "oligosaccharide".
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub rcsb_branched_component_count: Option<u32>,
+}
+
+/// A linear descriptor of the branched entity, e.g. WURCS, or Glycam condensed sequence.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct EntityBranchDescriptor {
+    pub descriptor: String,
+    /// E.g. "WURCS", "LINUCS", "Glycam Condensed Sequence".
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub program: Option<String>,
+    pub program_version: Option<String>,
+}
+
+/// A monomer (e.g. a saccharide) in the branched entity.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct EntityBranchListItem {
+    /// Chemical component ID, e.g. "NAG".
+    pub comp_id: String,
+    pub num: u32,
+    pub hetero: Option<String>,
+}
+
+/// A linkage between two monomers of the branched entity. The `entity_branch_list_num` fields
+/// index into the branch list.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct EntityBranchLink {
+    pub link_id: Option<u32>,
+    pub comp_id_1: Option<String>,
+    pub comp_id_2: Option<String>,
+    pub atom_id_1: Option<String>,
+    pub atom_id_2: Option<String>,
+    pub leaving_atom_id_1: Option<String>,
+    pub leaving_atom_id_2: Option<String>,
+    pub entity_branch_list_num_1: Option<u32>,
+    pub entity_branch_list_num_2: Option<u32>,
+    /// E.g. "sing".
+    pub value_order: Option<String>,
+    pub details: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct RcsbBranchedEntity {
+    pub pdbx_description: Option<String>,
+    pub formula_weight: Option<f32>,
+    pub pdbx_number_of_molecules: Option<u32>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct ReferenceIdentifier {
+    /// E.g. "GlyTouCan", "GlyGen".
+    pub resource_name: Option<String>,
+    pub resource_accession: Option<String>,
+    pub provenance_source: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct BranchedEntityContainerIdentifiers {
+    pub entry_id: String,
+    pub entity_id: String,
+    #[serde(default)]
+    pub asym_ids: Vec<String>,
+    #[serde(default)]
+    pub auth_asym_ids: Vec<String>,
+    #[serde(default)]
+    pub chem_comp_monomers: Vec<String>,
+    #[serde(default)]
+    pub reference_identifiers: Vec<ReferenceIdentifier>,
+}
+
+/// A branched entity, e.g. a glycan. From the `branched_entity` data API endpoint.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct BranchedEntity {
+    pub rcsb_id: String,
+    #[serde(default)]
+    pub pdbx_entity_branch: Option<EntityBranch>,
+    #[serde(default)]
+    pub pdbx_entity_branch_descriptor: Vec<EntityBranchDescriptor>,
+    #[serde(default)]
+    pub pdbx_entity_branch_list: Vec<EntityBranchListItem>,
+    #[serde(default)]
+    pub pdbx_entity_branch_link: Vec<EntityBranchLink>,
+    pub rcsb_branched_entity: Option<RcsbBranchedEntity>,
+    pub rcsb_branched_entity_container_identifiers: BranchedEntityContainerIdentifiers,
+}
+
+impl BranchedEntity {
+    /// The WURCS descriptor, if available.
+    pub fn wurcs(&self) -> Option<&str> {
+        self.pdbx_entity_branch_descriptor
+            .iter()
+            .find(|d| d.type_ == "WURCS")
+            .map(|d| d.descriptor.as_str())
+    }
+
+    /// GlyTouCan accession IDs, e.g. "G15407YE".
+    pub fn glytoucan_ids(&self) -> Vec<&str> {
+        self.rcsb_branched_entity_container_identifiers
+            .reference_identifiers
+            .iter()
+            .filter(|r| r.resource_name.as_deref() == Some("GlyTouCan"))
+            .filter_map(|r| r.resource_accession.as_deref())
+            .collect()
+    }
+
+    /// Count of each monomer (chemical component) in the entity, e.g. `("NAG", 2)`.
+    pub fn composition(&self) -> Vec<(String, usize)> {
+        let mut result: Vec<(String, usize)> = Vec::new();
+        for item in &self.pdbx_entity_branch_list {
+            match result.iter_mut().find(|(id, _)| *id == item.comp_id) {
+                Some((_, count)) => *count += 1,
+                None => result.push((item.comp_id.clone(), 1)),
+            }
+        }
+        result
+    }
 }
 
 #[derive(Default, Debug, Deserialize)]
@@ -377,25 +1057,42 @@ pub struct PdbData {
     pub title: String,
 }
 
-/// Get a semi-random protein released within the past week.
-/// https://search.rcsb.org/#search-example-12
-pub fn get_newly_released() -> Result<String, ReqError> {
-    let payload_search = PdbPayloadSearch {
-        return_type: ReturnType::Entry,
-        query: PdbSearchQuery {
-            type_: RcsbType::Terminal,
-            service: Service::Text,
-            parameters: PdbSearchParams {
-                attribute: Some("rcsb_accession_info.initial_release_date".to_owned()),
-                operator: Some(Operator::Greater),
-                value: Some("now-1w".to_owned()),
-                ..Default::default()
-            },
-        },
-        ..Default::default()
-    };
+/// The body the data API returns on failure, e.g. for an ID that doesn't exist.
+#[derive(Debug, Deserialize)]
+struct DataApiError {
+    status: u16,
+}
 
-    let payload_json = serde_json::to_string(&payload_search).unwrap();
+/// Make a GET request to the data API, and parse the result. Returns `ReqError::NotFound` if
+/// the API reports that the identifier doesn't exist.
+fn get_data<T: DeserializeOwned>(agent: &Agent, url: &str, ident: &str) -> Result<T, ReqError> {
+    let mut resp = agent.get(url).call()?;
+    let status = resp.status();
+    let body = resp.body_mut().read_to_string()?;
+
+    if status == StatusCode::NOT_FOUND {
+        return Err(ReqError::NotFound {
+            ident: ident.to_owned(),
+        });
+    }
+
+    if status != StatusCode::OK {
+        return match serde_json::from_str::<DataApiError>(&body) {
+            Ok(e) if e.status == 404 => Err(ReqError::NotFound {
+                ident: ident.to_owned(),
+            }),
+            _ => Err(ReqError::Http),
+        };
+    }
+
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Post a search payload to the search API, and parse its results. The API returns an empty
+/// body (HTTP 204) when there are no hits; we treat this as an empty result set.
+/// See [`SearchBuilder`] for a convenient way to construct the payload.
+pub fn search(payload: &PdbPayloadSearch) -> Result<PdbSearchResults, ReqError> {
+    let payload_json = serde_json::to_string(payload)?;
 
     let agent = make_agent();
 
@@ -406,15 +1103,176 @@ pub fn get_newly_released() -> Result<String, ReqError> {
         .body_mut()
         .read_to_string()?;
 
-    let search_data: PdbSearchResults = serde_json::from_str(&resp)?;
+    if resp.trim().is_empty() {
+        return Ok(Default::default());
+    }
+
+    Ok(serde_json::from_str(&resp)?)
+}
+
+/// Run a search that returns all hits, and return the entry identifiers only.
+fn search_all_idents(query: PdbSearchQuery) -> Result<Vec<String>, ReqError> {
+    let payload_search = PdbPayloadSearch {
+        return_type: ReturnType::Entry,
+        query,
+        request_options: Some(SearchRequestOptions {
+            return_all_hits: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let search_data = search(&payload_search)?;
+    Ok(search_data
+        .result_set
+        .into_iter()
+        .map(|r| r.identifier)
+        .collect())
+}
+
+/// Find all entries with an author matching this name. This matches against both the primary
+/// citation's authors, and the deposition (audit) authors. E.g. "Kuriyan, J.".
+pub fn search_by_author(name: &str) -> Result<Vec<String>, ReqError> {
+    let query = PdbSearchQuery::group(
+        LogicalOperator::Or,
+        vec![
+            PdbSearchQuery::text_attr(
+                "rcsb_primary_citation.rcsb_authors",
+                Operator::ContainsPhrase,
+                name,
+            ),
+            PdbSearchQuery::text_attr("audit_author.name", Operator::ContainsPhrase, name),
+        ],
+    );
+
+    search_all_idents(query)
+}
+
+/// Find all entries where a given chemical component (e.g. a ligand) is bound. `ccd_id` is the
+/// Chemical Component Dictionary identifier, e.g. "ATP" or "HEM".
+pub fn search_by_bound_ligand(ccd_id: &str) -> Result<Vec<String>, ReqError> {
+    let ccd_id = ccd_id.to_uppercase();
+
+    let query = PdbSearchQuery::group(
+        LogicalOperator::Or,
+        vec![
+            PdbSearchQuery::text_attr(
+                "rcsb_nonpolymer_instance_annotation.comp_id",
+                Operator::ExactMatch,
+                &ccd_id,
+            ),
+            PdbSearchQuery::text_attr(
+                "rcsb_nonpolymer_entity_container_identifiers.nonpolymer_comp_id",
+                Operator::ExactMatch,
+                &ccd_id,
+            ),
+        ],
+    );
+
+    search_all_idents(query)
+}
+
+/// Entry dates that can be searched by range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateField {
+    InitialRelease,
+    Deposit,
+    Revision,
+}
+
+impl DateField {
+    pub fn attribute(self) -> &'static str {
+        match self {
+            Self::InitialRelease => "rcsb_accession_info.initial_release_date",
+            Self::Deposit => "rcsb_accession_info.deposit_date",
+            Self::Revision => "rcsb_accession_info.revision_date",
+        }
+    }
+}
+
+/// Find all entries with a given date in a range, inclusive. `from` and `to` can be absolute
+/// (e.g. "2024-01-31"), or relative (e.g. "now-1w", "now-3M", "now").
+pub fn search_by_date_range(
+    field: DateField,
+    from: &str,
+    to: &str,
+) -> Result<Vec<String>, ReqError> {
+    search_all_idents(PdbSearchQuery::text_range(field.attribute(), from, to))
+}
+
+// The number of hits to request per page, when paginating through large result sets.
+const PAGE_SIZE: u32 = 1_000;
+
+/// Run a search one page at a time, returning all entry identifiers. Use this for queries that
+/// may return very large result sets. We sort by entry ID; for attribute queries, all hits have the
+/// same score, so the order isn't otherwise stable across pages.
+fn search_all_idents_paginated(query: PdbSearchQuery) -> Result<Vec<String>, ReqError> {
+    let mut payload_search = PdbPayloadSearch {
+        return_type: ReturnType::Entry,
+        query,
+        ..Default::default()
+    };
+
+    let mut result = Vec::new();
+    loop {
+        payload_search.request_options = Some(SearchRequestOptions {
+            paginate: Some(Paginate {
+                start: result.len() as u32,
+                rows: PAGE_SIZE,
+            }),
+            sort: Some(vec![Sort {
+                sort_by: "rcsb_entry_container_identifiers.entry_id".to_owned(),
+                direction: "asc".to_owned(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+
+        let search_data = search(&payload_search)?;
+        let page_len = search_data.result_set.len();
+
+        result.extend(search_data.result_set.into_iter().map(|r| r.identifier));
+
+        if page_len < PAGE_SIZE as usize || result.len() >= search_data.total_count as usize {
+            break;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Find all entries modified (revised) on or after a date, for incremental updates of a local
+/// mirror. `date` can be absolute (e.g. "2024-01-31"), or relative (e.g. "now-1w").
+pub fn entries_modified_since(date: &str) -> Result<Vec<String>, ReqError> {
+    search_all_idents_paginated(PdbSearchQuery::text_attr(
+        DateField::Revision.attribute(),
+        Operator::GreaterOrEqual,
+        date,
+    ))
+}
+
+/// Find all entries released on or after a date, for incremental updates of a local
+/// mirror. `date` can be absolute (e.g. "2024-01-31"), or relative (e.g. "now-1w").
+pub fn entries_released_since(date: &str) -> Result<Vec<String>, ReqError> {
+    search_all_idents_paginated(PdbSearchQuery::text_attr(
+        DateField::InitialRelease.attribute(),
+        Operator::GreaterOrEqual,
+        date,
+    ))
+}
+
+/// Get a semi-random protein released within the past week.
+/// https://search.rcsb.org/#search-example-12
+pub fn get_newly_released() -> Result<String, ReqError> {
+    let idents = search_by_date_range(DateField::InitialRelease, "now-1w", "now")?;
 
-    if search_data.result_set.is_empty() {
+    if idents.is_empty() {
         Err(ReqError::Http)
     } else {
         let mut rng = rand::rng();
-        let i = rng.random_range(0..search_data.result_set.len());
+        let i = rng.random_range(0..idents.len());
 
-        Ok(search_data.result_set[i].identifier.clone())
+        Ok(idents[i].clone())
     }
 }
 
@@ -427,12 +1285,13 @@ pub fn pdb_data_from_seq(aa_seq: &[AminoAcid]) -> Result<Vec<PdbData>, ReqError>
             type_: RcsbType::Terminal,
             service: Service::Sequence,
             parameters: PdbSearchParams {
-                value: Some(seq_aa_to_str(aa_seq)),
+                value: Some(SearchValue::Str(seq_aa_to_str(aa_seq))),
                 sequence_type: Some("protein".to_owned()),
                 evalue_cutoff: Some(1),
                 identity_cutoff: Some(0.9),
                 ..Default::default()
             },
+            ..Default::default()
         },
         request_options: Some(SearchRequestOptions {
             scoring_strategy: Some("sequence".to_owned()),
@@ -443,19 +1302,10 @@ pub fn pdb_data_from_seq(aa_seq: &[AminoAcid]) -> Result<Vec<PdbData>, ReqError>
 
     // todo: Limit the query to our result cap, instead of indexing after?
 
-    let payload_json = serde_json::to_string(&payload_search).unwrap();
+    let search_data = search(&payload_search)?;
 
     let agent = make_agent();
 
-    let resp: String = agent
-        .post(SEARCH_API_URL)
-        .header("Content-Type", "application/json")
-        .send(&payload_json)?
-        .body_mut()
-        .read_to_string()?;
-
-    let search_data: PdbSearchResults = serde_json::from_str(&resp)?;
-
     let mut result_search = Vec::new();
     for (i, r) in search_data.result_set.into_iter().enumerate() {
         if i < MAX_RESULTS {
@@ -465,13 +1315,11 @@ pub fn pdb_data_from_seq(aa_seq: &[AminoAcid]) -> Result<Vec<PdbData>, ReqError>
 
     let mut result = Vec::with_capacity(result_search.len());
     for r in result_search {
-        let resp = agent
-            .get(&format!("{DATA_API_URL}/{}", r.identifier))
-            .call()?
-            .body_mut()
-            .read_to_string()?;
-
-        let data: PdbDataResults = serde_json::from_str(&resp)?;
+        let data: PdbDataResults = get_data(
+            &agent,
+            &format!("{DATA_API_URL}/{}", r.identifier),
+            &r.identifier,
+        )?;
 
         result.push(PdbData {
             rcsb_id: r.identifier,
@@ -498,6 +1346,44 @@ pub fn open_3d_view(ident: &str) {
     }
 }
 
+/// Initial visualization states for the Mol* 3D viewer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ViewerPreset {
+    /// The default view of the first assembly.
+    Default,
+    /// Focus on a ligand and its interactions, by CCD ID, e.g. "ATP".
+    LigandFocus(String),
+    /// Color by validation (geometry and density fit) quality.
+    Validation,
+    /// Show the electron density around the model.
+    Density,
+    /// Show a specific assembly, e.g. "1", or "model" for the deposited coordinates.
+    Assembly(String),
+}
+
+/// The URL of the Mol* 3D viewer for a structure, set to a given preset. Useful for linking
+/// to a specific visualization state.
+pub fn url_3d_view(ident: &str, preset: &ViewerPreset) -> String {
+    match preset {
+        ViewerPreset::Default => format!("{RCSB_3D_VIEW_URL}/{ident}"),
+        ViewerPreset::LigandFocus(comp_id) => format!(
+            "{RCSB_3D_VIEW_URL}/{ident}?preset=ligandInteraction&label_comp_id={}",
+            comp_id.to_uppercase()
+        ),
+        ViewerPreset::Validation => format!("{RCSB_3D_VIEW_URL}/{ident}?preset=validation"),
+        ViewerPreset::Density => format!("{RCSB_3D_VIEW_URL}/{ident}?preset=density"),
+        ViewerPreset::Assembly(id) => format!("{RCSB_3D_VIEW_URL}/{ident}/{id}"),
+    }
+}
+
+/// Open the Mol* 3D viewer for a structure, set to a given preset.
+/// This works with 4-letter (legacy), and 12-letter IDs.
+pub fn open_3d_view_with(ident: &str, preset: &ViewerPreset) {
+    if let Err(e) = webbrowser::open(&url_3d_view(ident, preset)) {
+        eprintln!("Failed to open the web browser: {:?}", e);
+    }
+}
+
 /// Load PDB structure data in the PDBx/mmCIF format. This is a modern, text-based format.
 /// It avoids the XML, and limitations of the other two available formats.
 /// /// This works with 4-letter (legacy), and 12-letter IDs.
@@ -512,13 +1398,7 @@ pub fn open_structure(ident: &str) {
 pub fn load_metadata(ident: &str) -> Result<PdbMetaData, ReqError> {
     let agent = make_agent();
 
-    let resp = agent
-        .get(&format!("{DATA_API_URL}/{}", ident))
-        .call()?
-        .body_mut()
-        .read_to_string()?;
-
-    let data: PdbMetaDataResults = serde_json::from_str(&resp)?;
+    let data: PdbMetaDataResults = get_data(&agent, &format!("{DATA_API_URL}/{ident}"), ident)?;
 
     Ok(PdbMetaData {
         prim_cit_title: data.rcsb_primary_citation.title,
@@ -568,28 +1448,270 @@ fn validation_fo_fc_cif_gz_url(ident: &str) -> io::Result<String> {
 pub fn get_all_data(ident: &str) -> Result<PdbDataResults, ReqError> {
     let agent = make_agent();
 
-    let resp = agent
-        .get(&format!("{DATA_API_URL}/{}", ident))
-        .call()?
-        .body_mut()
-        .read_to_string()?;
+    get_data(&agent, &format!("{DATA_API_URL}/{ident}"), ident)
+}
 
-    Ok(serde_json::from_str(&resp)?)
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+struct AssemblyContainerIdentifiers {
+    #[serde(default)]
+    interface_ids: Vec<String>,
+}
+
+/// Deserializing only
+#[derive(Clone, Default, Debug, Deserialize)]
+struct AssemblyResp {
+    rcsb_assembly_container_identifiers: AssemblyContainerIdentifiers,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct InterfaceInfo {
+    /// Buried surface area, in Å².
+    pub interface_area: Option<f32>,
+    /// E.g. "homo_2", or "hetero_2".
+    pub interface_character: Option<String>,
+    /// E.g. "Protein (only)".
+    pub polymer_composition: Option<String>,
+    pub num_interface_residues: Option<u32>,
+    pub num_core_interface_residues: Option<u32>,
+    pub self_jaccard_contact_score: Option<f32>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct InterfacePartnerIdentifier {
+    pub entity_id: String,
+    /// The chain ID (label).
+    pub asym_id: String,
+}
+
+/// A residue range, using sequence (label) numbering.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct FeaturePosition {
+    pub beg_seq_id: u32,
+    pub end_seq_id: Option<u32>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct InterfacePartnerFeature {
+    /// E.g. "interface", or "core_interface".
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub feature_positions: Vec<FeaturePosition>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct InterfacePartner {
+    pub interface_partner_identifier: InterfacePartnerIdentifier,
+    #[serde(default)]
+    pub interface_partner_feature: Vec<InterfacePartnerFeature>,
+}
+
+impl InterfacePartner {
+    /// Sequence IDs of this partner's residues at the interface.
+    pub fn residue_seq_ids(&self) -> Vec<u32> {
+        let mut result = Vec::new();
+        for feature in &self.interface_partner_feature {
+            if feature.type_ != "interface" {
+                continue;
+            }
+            for pos in &feature.feature_positions {
+                let end = pos.end_seq_id.unwrap_or(pos.beg_seq_id);
+                result.extend(pos.beg_seq_id..=end);
+            }
+        }
+        result
+    }
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct InterfaceContainerIdentifiers {
+    pub entry_id: String,
+    pub assembly_id: String,
+    pub interface_id: String,
+    pub interface_entity_id: Option<String>,
+}
+
+/// A macromolecular interface between two chains of an assembly. From the `interface` data API
+/// endpoint.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct Interface {
+    pub rcsb_id: String,
+    pub rcsb_interface_info: InterfaceInfo,
+    #[serde(default)]
+    pub rcsb_interface_partner: Vec<InterfacePartner>,
+    pub rcsb_interface_container_identifiers: InterfaceContainerIdentifiers,
+}
+
+/// Load all interfaces between chains of an assembly. `assembly_id` is usually "1".
+pub fn get_interfaces(ident: &str, assembly_id: &str) -> Result<Vec<Interface>, ReqError> {
+    let agent = make_agent();
+
+    let assembly: AssemblyResp = get_data(
+        &agent,
+        &format!("{DATA_API_CORE_URL}/assembly/{ident}/{assembly_id}"),
+        ident,
+    )?;
+    let interface_ids = assembly.rcsb_assembly_container_identifiers.interface_ids;
+
+    let mut result = Vec::with_capacity(interface_ids.len());
+    for interface_id in interface_ids {
+        result.push(get_data(
+            &agent,
+            &format!("{DATA_API_CORE_URL}/interface/{ident}/{assembly_id}/{interface_id}"),
+            ident,
+        )?);
+    }
+
+    Ok(result)
+}
+
+/// A source organism of a polymer entity.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct SourceOrganism {
+    pub ncbi_taxonomy_id: Option<u32>,
+    pub ncbi_scientific_name: Option<String>,
+    pub ncbi_common_name: Option<String>,
+    /// E.g. "natural", "genetically engineered", or "synthetic".
+    pub source_type: Option<String>,
+}
+
+/// Deserializing only
+#[derive(Clone, Default, Debug, Deserialize)]
+struct PolymerEntityOrganismsResp {
+    #[serde(default)]
+    rcsb_entity_source_organism: Vec<SourceOrganism>,
+}
+
+/// Find the source organisms of all polymer entities in an entry, with their NCBI taxonomy IDs.
+/// Duplicates across entities are removed.
+pub fn get_source_organisms(ident: &str) -> Result<Vec<SourceOrganism>, ReqError> {
+    let entry = get_all_data(ident)?;
+
+    let Some(entity_ids) = entry.rcsb_entry_container_identifiers.polymer_entity_ids else {
+        return Ok(Vec::new());
+    };
+
+    let agent = make_agent();
+
+    let mut result: Vec<SourceOrganism> = Vec::new();
+    for entity_id in entity_ids {
+        let entity: PolymerEntityOrganismsResp = get_data(
+            &agent,
+            &format!("{DATA_API_CORE_URL}/polymer_entity/{ident}/{entity_id}"),
+            ident,
+        )?;
+
+        for org in entity.rcsb_entity_source_organism {
+            if !result.contains(&org) {
+                result.push(org);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Load all branched entities (e.g. glycans) for an entry. Returns an empty Vec if there are
+/// none.
+pub fn get_branched_entities(ident: &str) -> Result<Vec<BranchedEntity>, ReqError> {
+    let entry = get_all_data(ident)?;
+
+    let Some(entity_ids) = entry.rcsb_entry_container_identifiers.branched_entity_ids else {
+        return Ok(Vec::new());
+    };
+
+    let agent = make_agent();
+
+    let mut result = Vec::with_capacity(entity_ids.len());
+    for entity_id in entity_ids {
+        result.push(get_data(
+            &agent,
+            &format!("{DATA_API_CORE_URL}/branched_entity/{ident}/{entity_id}"),
+            ident,
+        )?);
+    }
+
+    Ok(result)
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct DrugbankContainerIdentifiers {
+    pub drugbank_id: String,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct DrugbankInfo {
+    pub drugbank_id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub indication: Option<String>,
+    pub mechanism_of_action: Option<String>,
+    pub pharmacology: Option<String>,
+    pub cas_number: Option<String>,
+    /// Anatomical Therapeutic Chemical classification codes, e.g. "C01EB10".
+    #[serde(default)]
+    pub atc_codes: Vec<String>,
+    #[serde(default)]
+    pub brand_names: Vec<String>,
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+    #[serde(default)]
+    pub drug_categories: Vec<String>,
+    /// E.g. "approved", "investigational".
+    #[serde(default)]
+    pub drug_groups: Vec<String>,
+    #[serde(default)]
+    pub affected_organisms: Vec<String>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct DrugbankTarget {
+    pub name: Option<String>,
+    /// E.g. "target", "enzyme", "transporter", "carrier".
+    pub interaction_type: Option<String>,
+    pub ordinal: Option<u32>,
+    pub organism_common_name: Option<String>,
+    /// E.g. "UniProt".
+    pub reference_database_name: Option<String>,
+    /// E.g. a UniProt accession.
+    pub reference_database_accession_code: Option<String>,
+    pub seq_one_letter_code: Option<String>,
+    #[serde(default)]
+    pub target_actions: Vec<String>,
+}
+
+/// DrugBank annotations that RCSB aggregates for a chemical component. From the `drugbank` data
+/// API endpoint.
+#[derive(Clone, Default, Debug, PartialEq, Deserialize)]
+pub struct DrugbankData {
+    pub rcsb_id: String,
+    pub drugbank_container_identifiers: DrugbankContainerIdentifiers,
+    pub drugbank_info: DrugbankInfo,
+    #[serde(default)]
+    pub drugbank_target: Vec<DrugbankTarget>,
+}
+
+/// Load DrugBank information for a chemical component (e.g. a ligand), using its CCD identifier,
+/// e.g. "ATP". This doesn't require a DrugBank account.
+pub fn get_drugbank_info(ccd_id: &str) -> Result<DrugbankData, ReqError> {
+    let agent = make_agent();
+
+    let ccd_id = ccd_id.to_uppercase();
+    get_data(
+        &agent,
+        &format!("{DATA_API_CORE_URL}/drugbank/{ccd_id}"),
+        &ccd_id,
+    )
 }
 
 pub fn map_gz_url(ident: &str) -> Result<String, ReqError> {
     // todo: Cut down on the required fields for this, to save data(?)
     let agent = make_agent();
 
-    let resp = agent
-        .get(&format!("{DATA_API_URL}/{}", ident))
-        .call()?
-        .body_mut()
-        .read_to_string()?;
-
     // note: This DB ident is available under pdbx_database_related, rcsb_entry_container_identifiers, and rcsb_external_references
 
-    let data: PdbDataResults = serde_json::from_str(&resp)?;
+    let data: PdbDataResults = get_data(&agent, &format!("{DATA_API_URL}/{ident}"), ident)?;
 
     for db in &data.database2 {
         if &db.database_id == "EMDB" {
@@ -639,6 +1761,24 @@ pub fn load_cif(ident: &str) -> Result<String, ReqError> {
     decode_gz_str_resp(resp)
 }
 
+/// Where a structure file was loaded from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CifSource {
+    Rcsb,
+    /// The PDBe "updated" mmCIF.
+    Pdbe,
+}
+
+/// Download a mmCIF file from the RCSB, as [`load_cif`]. If that fails, e.g. from an outage, load
+/// the PDBe's updated mmCIF for the entry instead. Returns the CIF string, and which source it
+/// came from. If both fail, returns the PDBe error.
+pub fn load_cif_with_fallback(ident: &str) -> Result<(String, CifSource), ReqError> {
+    match load_cif(ident) {
+        Ok(cif) => Ok((cif, CifSource::Rcsb)),
+        Err(_) => Ok((pdbe::load_updated_cif(ident)?, CifSource::Pdbe)),
+    }
+}
+
 /// Download a validation mmCIF file (Related to electron density??) from the RCSB, returning an CIF string.
 ///
 pub fn load_validation_cif(ident: &str) -> Result<String, ReqError> {
@@ -733,3 +1873,146 @@ pub fn get_files_avail(ident: &str) -> Result<FilesAvailable, ReqError> {
         map,
     })
 }
+
+/// Wraps a reader, reporting the number of bytes read so far.
+struct ProgressReader<R, F> {
+    inner: R,
+    read: u64,
+    total: Option<u64>,
+    progress: F,
+}
+
+impl<R: Read, F: FnMut(u64, Option<u64>)> Read for ProgressReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        (self.progress)(self.read, self.total);
+        Ok(n)
+    }
+}
+
+/// Download a gzipped CIF file, and save it, decompressed, to `path`.
+fn download_cif_gz(
+    url: &str,
+    path: &Path,
+    progress: &mut impl FnMut(u64, Option<u64>),
+) -> Result<(), ReqError> {
+    let agent = make_agent_download();
+
+    let resp = agent.get(url).call()?;
+    if resp.status() != StatusCode::OK {
+        return Err(ReqError::Http);
+    }
+
+    let total = resp.body().content_length();
+    let reader = ProgressReader {
+        inner: resp.into_body().into_reader(),
+        read: 0,
+        total,
+        progress,
+    };
+
+    let mut decoder = GzDecoder::new(reader);
+    let mut file = BufWriter::new(File::create(path)?);
+
+    io::copy(&mut decoder, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Download the full Chemical Component Dictionary (CCD), and the BIRD Prototype Reference
+/// Dictionary (PRD), into a directory, for offline use. These are saved decompressed, as
+/// `components.cif` and `prd-all.cif`. Returns their paths, in that order.
+///
+/// `progress` is called with the (compressed) bytes downloaded so far, and the total size if
+/// known. It's called separately for each file. These files are large; the CCD is several
+/// hundred MB decompressed.
+pub fn download_ccd_dictionary(
+    dir: &Path,
+    mut progress: impl FnMut(u64, Option<u64>),
+) -> Result<(PathBuf, PathBuf), ReqError> {
+    let path_ccd = dir.join("components.cif");
+    let path_prd = dir.join("prd-all.cif");
+
+    download_cif_gz(CCD_URL, &path_ccd, &mut progress)?;
+    download_cif_gz(PRD_URL, &path_prd, &mut progress)?;
+
+    Ok((path_ccd, path_prd))
+}
+
+/// An index of the data blocks in a multi-block CIF file, such as the CCD or PRD dictionary
+/// saved by [`download_ccd_dictionary`]. This allows looking up a single chemical component
+/// without loading the whole file into memory.
+#[derive(Clone, Debug)]
+pub struct CcdIndex {
+    pub path: PathBuf,
+    /// Block name (e.g. the chemical component ID, "ATP") to (byte offset, length).
+    offsets: HashMap<String, (u64, u64)>,
+}
+
+impl CcdIndex {
+    /// Scan the file for its `data_` block headers.
+    pub fn build(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut offsets = HashMap::new();
+        let mut current: Option<(String, u64)> = None;
+
+        let mut pos = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let n = reader.read_line(&mut line)?;
+            if n == 0 {
+                break;
+            }
+
+            if let Some(name) = line.strip_prefix("data_") {
+                if let Some((prev, start)) = current.take() {
+                    offsets.insert(prev, (start, pos - start));
+                }
+                current = Some((name.trim().to_uppercase(), pos));
+            }
+
+            pos += n as u64;
+        }
+
+        if let Some((prev, start)) = current {
+            offsets.insert(prev, (start, pos - start));
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            offsets,
+        })
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.offsets.contains_key(&id.to_uppercase())
+    }
+
+    /// The number of components in the index.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Load the CIF text of a single component, e.g. "ATP". Returns `None` if it's not present.
+    pub fn get(&self, id: &str) -> io::Result<Option<String>> {
+        let Some(&(start, len)) = self.offsets.get(&id.to_uppercase()) else {
+            return Ok(None);
+        };
+
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(start))?;
+
+        let mut result = String::with_capacity(len as usize);
+        file.take(len).read_to_string(&mut result)?;
+
+        Ok(Some(result))
+    }
+}